@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::ToTokens;
 use syn::{
-    parse::{Parse, Parser}, punctuated::Punctuated, token::Comma, AttrStyle, DeriveInput, ItemStruct, LitStr
+    parse::{Parse, Parser}, punctuated::Punctuated, token::Comma, AttrStyle, DeriveInput, ItemStruct
 };
 
 #[macro_use]
@@ -126,6 +126,43 @@ impl ConcatTokenStream for proc_macro2::TokenStream {
     }
 }
 
+/// Implements `ExecuteTick` for `ident`, evaluating the node's pre-conditions
+/// before `body` and its post-conditions after. `body` must evaluate to a `NodeResult`.
+fn execute_tick_impl(
+    ident: &syn::Ident,
+    where_clause: proc_macro2::TokenStream,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        impl ::behaviortree_rs::nodes::ExecuteTick for #ident #where_clause {
+            fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
+                let span = ::behaviortree_rs::spans::tick_span(self);
+
+                ::std::boxed::Box::pin(::behaviortree_rs::spans::instrument_tick(span, async move {
+                    ::behaviortree_rs::loggers::notify_tick_start(self);
+
+                    let result = async {
+                        let status = match ::behaviortree_rs::nodes::check_pre_conditions(self).await? {
+                            Some(status) => status,
+                            None => async { #body }.await?,
+                        };
+
+                        ::behaviortree_rs::nodes::check_post_conditions(self, &status).await?;
+                        <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::set_status(self, status.clone());
+
+                        Ok::<_, ::behaviortree_rs::nodes::NodeError>(status)
+                    }
+                    .await;
+
+                    ::behaviortree_rs::loggers::notify_tick_end(self, &result);
+
+                    result
+                }))
+            }
+        }
+    }
+}
+
 fn create_bt_node(
     args: TokenStream,
     mut item: ItemStruct,
@@ -249,6 +286,10 @@ fn create_bt_node(
                     // Add proper derive macros
                     derives.push(quote! { ::behaviortree_rs::derive::ActionNode, ::behaviortree_rs::derive::SyncActionNode });
                 }
+                "ConditionNode" => {
+                    // Conditions are ticked like a SyncActionNode
+                    derives.push(quote! { ::behaviortree_rs::derive::ConditionNode, ::behaviortree_rs::derive::SyncActionNode });
+                }
                 "StatefulActionNode" => {
                     // Add StatefulActionNode-specific fields
                     fields.named.push(
@@ -263,7 +304,7 @@ fn create_bt_node(
                     // impl empty tick function
                     extra_impls = extra_impls.concat_blocks(quote! {
                         impl ::behaviortree_rs::nodes::AsyncTick for #item_ident {
-                            fn tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                            fn tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
                                 ::std::boxed::Box::pin(async move {
                                     Ok(::behaviortree_rs::basic_types::NodeStatus::Idle)
                                 })
@@ -273,40 +314,47 @@ fn create_bt_node(
 
                     match runtime_str.as_str() {
                         "Async" => {
+                            // Lets the node be driven from sync code. Inside an async
+                            // runtime, methods that have to wait return NodeError::WouldBlock
                             extra_impls = extra_impls.concat_blocks(quote! {
                                 impl ::behaviortree_rs::nodes::action::SyncStatefulActionNode for #item_ident {
                                     fn on_start(&mut self) -> ::behaviortree_rs::NodeResult {
-                                        ::behaviortree_rs::sync::block_on(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self))
+                                        ::behaviortree_rs::sync::run_sync(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self))?
                                     }
 
                                     fn on_running(&mut self) -> ::behaviortree_rs::NodeResult {
-                                        ::behaviortree_rs::sync::block_on(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self))
+                                        ::behaviortree_rs::sync::run_sync(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self))?
                                     }
 
                                     fn on_halted(&mut self) {
-                                        ::behaviortree_rs::sync::block_on(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_halted(self))
+                                        if let Err(e) = ::behaviortree_rs::sync::run_sync(::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_halted(self)) {
+                                            ::log::warn!("[behaviortree_rs]: {}: {e}", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+                                        }
                                     }
                                 }
                             });
                         }
                         "Sync" => {
+                            // Call the sync methods without spawning a task, so the futures
+                            // complete on their first poll and SyncTree never has to block
+                            // on them
                             extra_impls = extra_impls.concat_blocks(quote! {
                                 impl ::behaviortree_rs::nodes::action::AsyncStatefulActionNode for #item_ident {
-                                    fn on_start(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                                    fn on_start(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_start(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_start(self))
                                         })
                                     }
 
-                                    fn on_running(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                                    fn on_running(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_running(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_running(self))
                                         })
                                     }
 
-                                    fn on_halted(&mut self) -> ::behaviortree_rs::sync::BoxFuture<()>{
+                                    fn on_halted(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ()>{
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_halted(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_halted(self))
                                         })
                                     }
                                 }
@@ -396,8 +444,9 @@ fn create_bt_node(
             }
         });
 
-    match runtime_str.as_str() {
-        "Async" => {
+    match (type_ident.as_str(), runtime_str.as_str()) {
+        // StatefulActionNodes are ticked through on_start() and on_running()
+        ("StatefulActionNode", _) | (_, "Async") => {
             extra_impls = extra_impls.concat_blocks(quote! {
                 impl ::behaviortree_rs::nodes::SyncTick for #item_ident {
                     fn tick(&mut self) -> ::behaviortree_rs::NodeResult {
@@ -408,20 +457,22 @@ fn create_bt_node(
                 impl ::behaviortree_rs::nodes::SyncHalt for #item_ident {}
             });
         }
-        "Sync" => {
+        (_, "Sync") => {
+            // Call the sync methods without spawning a task, so the futures
+            // complete on their first poll and SyncTree never has to block on them
             extra_impls = extra_impls.concat_blocks(quote! {
                 impl ::behaviortree_rs::nodes::AsyncTick for #item_ident {
-                    fn tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                    fn tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
                         ::std::boxed::Box::pin(async move {
-                            ::behaviortree_rs::sync::spawn_blocking(|| <#item_ident as ::behaviortree_rs::nodes::SyncTick>::tick(self)).await
+                            ::behaviortree_rs::sync::block_in_place(|| <#item_ident as ::behaviortree_rs::nodes::SyncTick>::tick(self))
                         })
                     }
                 }
 
                 impl ::behaviortree_rs::nodes::AsyncHalt for #item_ident {
-                    fn halt(&mut self) -> ::behaviortree_rs::sync::BoxFuture<()> {
+                    fn halt(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ()> {
                         ::std::boxed::Box::pin(async move {
-                            ::behaviortree_rs::sync::spawn_blocking(|| <#item_ident as ::behaviortree_rs::nodes::SyncHalt>::halt(self)).await
+                            ::behaviortree_rs::sync::block_in_place(|| <#item_ident as ::behaviortree_rs::nodes::SyncHalt>::halt(self))
                         })
                     }
                 }
@@ -465,12 +516,14 @@ fn create_bt_node(
 ///
 /// Supported options:
 /// - `SyncActionNode`
+/// - `ConditionNode`
 /// - `StatefulActionNode`
 /// - `ControlNode`
 /// - `DecoratorNode`
 ///
 /// By default, the tick method implementation is `async`. To specify this explicitly (or
-/// make it synchronous), add `Async` or `Sync` after the node type.
+/// make it synchronous), add `Async` or `Sync` after the node type. The methods of `Sync`
+/// nodes are called through `behaviortree_rs::sync::block_in_place()`, without spawning a task.
 ///
 /// ===
 ///
@@ -484,7 +537,7 @@ fn create_bt_node(
 ///
 /// // Now I need to `impl TreeNode`
 /// impl AsyncTick for MyActionNode {
-///     fn tick(&mut self) -> BoxFuture<NodeResult> {
+///     fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
 ///         Box::pin(async move {
 ///             // Do something here
 ///             // ...
@@ -558,7 +611,7 @@ fn create_bt_node(
 ///
 /// // Now I need to `impl TreeNode`
 /// impl AsyncTick for MyActionNode {
-///     fn tick(&mut self) -> BoxFuture<NodeResult> {
+///     fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
 ///         Box::pin(async move {
 ///             Ok(NodeStatus::Success)
 ///         })
@@ -591,6 +644,10 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
                 &self.name
             }
 
+            fn set_name(&mut self, name: String) {
+                self.name = name;
+            }
+
             fn path(&self) -> &String {
                 &self.config.path
             }
@@ -600,11 +657,14 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
             }
 
             fn reset_status(&mut self) {
-                self.status = ::behaviortree_rs::basic_types::NodeStatus::Idle
+                <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::set_status(self, ::behaviortree_rs::basic_types::NodeStatus::Idle)
             }
 
             fn set_status(&mut self, status: ::behaviortree_rs::basic_types::NodeStatus) {
-                self.status = status;
+                if self.status != status {
+                    let prev_status = ::std::mem::replace(&mut self.status, status);
+                    ::behaviortree_rs::loggers::notify_status_change(self, prev_status);
+                }
             }
 
             fn config(&self) -> &::behaviortree_rs::nodes::NodeConfig {
@@ -615,7 +675,10 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
                 &mut self.config
             }
 
-            fn into_boxed(self) -> Box<dyn ::behaviortree_rs::nodes::TreeNodeBase> {
+            fn into_boxed(self) -> ::behaviortree_rs::nodes::TreeNodePtr
+            where
+                Self: Sized + Send + Sync,
+            {
                 Box::new(self)
             }
         }
@@ -631,13 +694,23 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
 pub fn derive_action_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let ident = input.ident;
+    TokenStream::from(action_node_impl(&input.ident, quote! { ::behaviortree_rs::basic_types::NodeType::Action }))
+}
 
-    let expanded = quote! {
+/// Same as `ActionNode`, but the node reports `NodeType::Condition`
+#[proc_macro_derive(ConditionNode)]
+pub fn derive_condition_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    TokenStream::from(action_node_impl(&input.ident, quote! { ::behaviortree_rs::basic_types::NodeType::Condition }))
+}
+
+fn action_node_impl(ident: &syn::Ident, node_type: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
         impl ::behaviortree_rs::nodes::ActionNode for #ident {
-            fn execute_action_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+            fn execute_action_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ::behaviortree_rs::NodeResult> {
                 ::std::boxed::Box::pin(async move {
-                    match self.tick().await? {
+                    match <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await? {
                         ::behaviortree_rs::basic_types::NodeStatus::Idle => Err(::behaviortree_rs::nodes::NodeError::StatusError(self.config.path.clone(), "Idle".to_string())),
                         status => Ok(status)
                     }
@@ -647,14 +720,22 @@ pub fn derive_action_node(input: TokenStream) -> TokenStream {
 
         impl ::behaviortree_rs::nodes::ActionNodeBase for #ident {}
 
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {
+            fn child_nodes(&self) -> &[::behaviortree_rs::nodes::TreeNodePtr] {
+                &[]
+            }
+
+            fn child_nodes_mut(&mut self) -> &mut [::behaviortree_rs::nodes::TreeNodePtr] {
+                &mut []
+            }
+        }
+
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
-                ::behaviortree_rs::basic_types::NodeType::Action
+                #node_type
             }
         }
-    };
-
-    TokenStream::from(expanded)
+    }
 }
 
 #[proc_macro_derive(ControlNode)]
@@ -663,6 +744,11 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
 
     let ident = input.ident;
 
+    let execute_tick = execute_tick_impl(&ident, quote! {}, quote! {
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+        <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await
+    });
+
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::ControlNode for #ident {
             fn add_child(&mut self, child: ::behaviortree_rs::nodes::TreeNodePtr) {
@@ -673,14 +759,14 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
                 &self.children
             }
 
-            fn halt_child(&mut self, index: usize) -> ::behaviortree_rs::sync::BoxFuture<Result<(), ::behaviortree_rs::nodes::NodeError>> {
+            fn halt_child(&mut self, index: usize) -> ::behaviortree_rs::sync::BoxFuture<'_, Result<(), ::behaviortree_rs::nodes::NodeError>> {
                 ::std::boxed::Box::pin(async move {
                     match self.children.get_mut(index) {
                         Some(child) => {
                             if child.status() == ::behaviortree_rs::nodes::NodeStatus::Running {
                                 let child_ptr: *mut _ = &mut **child;
                                 unsafe {
-                                    ::behaviortree_rs::nodes::AsyncHalt::halt(&mut *child_ptr).await;
+                                    ::behaviortree_rs::nodes::halt_node(&mut *child_ptr).await;
                                 }
                             }
                             Ok(child.reset_status())
@@ -690,7 +776,7 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
                 })
             }
 
-            fn halt_children(&mut self, start: usize) -> ::behaviortree_rs::sync::BoxFuture<Result<(), ::behaviortree_rs::nodes::NodeError>> {
+            fn halt_children(&mut self, start: usize) -> ::behaviortree_rs::sync::BoxFuture<'_, Result<(), ::behaviortree_rs::nodes::NodeError>> {
                 ::std::boxed::Box::pin(async move {
 
                     if start >= self.children.len() {
@@ -707,24 +793,27 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
                 })
             }
 
-            fn reset_children(&mut self) -> ::behaviortree_rs::sync::BoxFuture<()> {
+            fn reset_children(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ()> {
                 ::std::boxed::Box::pin(async move {
                     self.halt_children(0).await.unwrap();
                 })
             }
         }
 
-        impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
-            fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
-                ::std::boxed::Box::pin(async move {
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                    <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await
-                })
-            }
-        }
+        #execute_tick
 
         impl ::behaviortree_rs::nodes::ControlNodeBase for #ident {}
 
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {
+            fn child_nodes(&self) -> &[::behaviortree_rs::nodes::TreeNodePtr] {
+                &self.children
+            }
+
+            fn child_nodes_mut(&mut self) -> &mut [::behaviortree_rs::nodes::TreeNodePtr] {
+                &mut self.children
+            }
+        }
+
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
                 ::behaviortree_rs::basic_types::NodeType::Control
@@ -741,6 +830,15 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
 
     let ident = input.ident;
 
+    let execute_tick = execute_tick_impl(&ident, quote! {}, quote! {
+        if self.child.is_none() {
+            return Err(::behaviortree_rs::nodes::NodeError::ChildMissing);
+        }
+
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
+        <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await
+    });
+
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::DecoratorNode for #ident {
             fn set_child(&mut self, child: ::behaviortree_rs::nodes::TreeNodePtr) {
@@ -754,20 +852,20 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn halt_child(&mut self) -> BoxFuture<()> {
+            fn halt_child(&mut self) -> BoxFuture<'_, ()> {
                 ::std::boxed::Box::pin(async move {
                     self.reset_child().await;
                 })
             }
 
-            fn reset_child(&mut self) -> BoxFuture<()> {
+            fn reset_child(&mut self) -> BoxFuture<'_, ()> {
                 ::std::boxed::Box::pin(async move {
                     if let Some(child) = self.child.as_mut() {
                         let mut child = child;
                         if matches!(child.status(), ::behaviortree_rs::basic_types::NodeStatus::Running) {
                             let child_ptr: *mut _ = &mut **child;
                             unsafe {
-                                ::behaviortree_rs::nodes::AsyncHalt::halt(&mut *child_ptr).await;
+                                ::behaviortree_rs::nodes::halt_node(&mut *child_ptr).await;
                             }
                         }
 
@@ -777,20 +875,19 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
-            fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
-                ::std::boxed::Box::pin(async move {
-                    if self.child.is_none() {
-                        return Err(::behaviortree_rs::nodes::NodeError::ChildMissing);
-                    }
+        #execute_tick
 
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
-                    self.tick().await
-                })
+        impl ::behaviortree_rs::nodes::DecoratorNodeBase for #ident {}
+
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {
+            fn child_nodes(&self) -> &[::behaviortree_rs::nodes::TreeNodePtr] {
+                self.child.as_slice()
             }
-        }
 
-        impl ::behaviortree_rs::nodes::DecoratorNodeBase for #ident {}
+            fn child_nodes_mut(&mut self) -> &mut [::behaviortree_rs::nodes::TreeNodePtr] {
+                self.child.as_mut_slice()
+            }
+        }
 
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
@@ -809,19 +906,13 @@ pub fn derive_sync_action_node(input: TokenStream) -> TokenStream {
 
     let ident = input.ident;
 
-    let expanded = quote! {
-        impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
-            fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
-                ::std::boxed::Box::pin(async move {
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
-                    match <Self as ::behaviortree_rs::nodes::ActionNode>::execute_action_tick(self).await? {
-                        ::behaviortree_rs::basic_types::NodeStatus::Running => Err(::behaviortree_rs::nodes::NodeError::StatusError(self.config.path.clone(), "Running".to_string())),
-                        status => Ok(status)
-                    }
-                })
-            }
+    let expanded = execute_tick_impl(&ident, quote! {}, quote! {
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
+        match <Self as ::behaviortree_rs::nodes::ActionNode>::execute_action_tick(self).await? {
+            ::behaviortree_rs::basic_types::NodeStatus::Running => Err(::behaviortree_rs::nodes::NodeError::StatusError(self.config.path.clone(), "Running".to_string())),
+            status => Ok(status)
         }
-    };
+    });
 
     TokenStream::from(expanded)
 }
@@ -833,46 +924,42 @@ pub fn derive_stateful_action_node(input: TokenStream) -> TokenStream {
 
     let ident = input.ident;
 
-    let expanded = quote! {
-        impl ::behaviortree_rs::nodes::ExecuteTick for #ident where #ident: ::behaviortree_rs::nodes::AsyncStatefulActionNode {
-            fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
-                ::std::boxed::Box::pin(async move {
-                    let prev_status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
-
-                    let new_status = match prev_status {
-                        ::behaviortree_rs::basic_types::NodeStatus::Idle => {
-                            ::log::debug!("[behaviortree_rs]: {}::on_start()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                            let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self).await?;
-                            if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
-                                return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_start()", self.config.path), "Idle".to_string()))
-                            }
-                            new_status
-                        }
-                        ::behaviortree_rs::basic_types::NodeStatus::Running => {
-                            ::log::debug!("[behaviortree_rs]: {}::on_running()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                            let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self).await?;
-                            if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
-                                return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_running()", self.config.path), "Idle".to_string()))
-                            }
-                            new_status
-                        }
-                        prev_status => prev_status
-                    };
-
-                    <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::set_status(self, new_status.clone());
+    let execute_tick = execute_tick_impl(&ident, quote! { where #ident: ::behaviortree_rs::nodes::AsyncStatefulActionNode }, quote! {
+        let prev_status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
 
-                    Ok(new_status)
-                })
+        let new_status = match prev_status {
+            ::behaviortree_rs::basic_types::NodeStatus::Idle => {
+                ::log::debug!("[behaviortree_rs]: {}::on_start()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+                let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self).await?;
+                if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
+                    return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_start()", self.config.path), "Idle".to_string()))
+                }
+                new_status
             }
-        }
+            ::behaviortree_rs::basic_types::NodeStatus::Running => {
+                ::log::debug!("[behaviortree_rs]: {}::on_running()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+                let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self).await?;
+                if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
+                    return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_running()", self.config.path), "Idle".to_string()))
+                }
+                new_status
+            }
+            prev_status => prev_status
+        };
+
+        Ok(new_status)
+    });
+
+    let expanded = quote! {
+        #execute_tick
 
         impl ::behaviortree_rs::nodes::AsyncHalt for #ident {
-            fn halt(&mut self) -> ::behaviortree_rs::sync::BoxFuture<()> {
+            fn halt(&mut self) -> ::behaviortree_rs::sync::BoxFuture<'_, ()> {
                 ::std::boxed::Box::pin(async move {
                     self.halt_requested = true;
 
                     if matches!(<Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self), ::behaviortree_rs::basic_types::NodeStatus::Running) {
-                        self.on_halted().await;
+                        ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_halted(self).await;
                     }
 
                     <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::reset_status(self);
@@ -952,14 +1039,38 @@ fn build_node(node: &NodeRegistration) -> proc_macro2::TokenStream {
     quote! {
         {
             let mut node = #node_type::new(#name, config #cloned_names);
-            let manifest = ::behaviortree_rs::basic_types::TreeNodeManifest {
+            <#node_type as ::behaviortree_rs::nodes::TreeNodeDefaults>::config_mut(&mut node).set_manifest(node_manifest.clone());
+            node
+        }
+    }
+}
+
+/// Creates the manifest of the node once, when it's registered, from an
+/// instance that's never ticked.
+fn build_manifest(node: &NodeRegistration) -> proc_macro2::TokenStream {
+    let NodeRegistration {
+        factory: _,
+        name,
+        node_type,
+        params
+    } = node;
+
+    let cloned_names = (0..params.len())
+        .fold(quote!{}, |acc, i| {
+            let arg_name = Ident::new(&format!("arg{i}"), Span::call_site());
+            quote!{ #acc, #arg_name.clone() }
+        });
+
+    quote! {
+        {
+            let config = ::behaviortree_rs::nodes::NodeConfig::new(blackboard.clone());
+            let node = #node_type::new(#name, config #cloned_names);
+            ::std::sync::Arc::new(::behaviortree_rs::basic_types::TreeNodeManifest {
                 node_type: <#node_type as ::behaviortree_rs::nodes::GetNodeType>::node_type(&node),
                 registration_id: #name.into(),
                 ports: <#node_type as ::behaviortree_rs::nodes::NodePorts>::provided_ports(&node),
                 description: ::std::string::String::new(),
-            };
-            <#node_type as ::behaviortree_rs::nodes::TreeNodeDefaults>::config_mut(&mut node).set_manifest(::std::sync::Arc::new(manifest));
-            node
+            })
         }
     }
 }
@@ -984,6 +1095,7 @@ fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream,
         });
 
     let node = build_node(&node_registration);
+    let manifest = build_manifest(&node_registration);
 
     let extra_steps = match node_type {
         NodeTypeInternal::Control => quote! { 
@@ -1001,6 +1113,9 @@ fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream,
 
             #param_clone_expr
 
+            let manifest = #manifest;
+            let node_manifest = manifest.clone();
+
             let node_fn = move |
                 config: ::behaviortree_rs::nodes::NodeConfig,
                 mut children: ::std::vec::Vec<::std::boxed::Box<dyn ::behaviortree_rs::nodes::TreeNodeBase + Send + Sync>>
@@ -1013,7 +1128,7 @@ fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream,
                 ::std::boxed::Box::new(node)
             };
 
-            #factory.register_node(#name, node_fn, #node_type_token);
+            #factory.register_node_with_manifest(#name, node_fn, #node_type_token, manifest);
         }
     };
 
@@ -1047,6 +1162,27 @@ pub fn register_action_node(input: TokenStream) -> TokenStream {
     register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Action }, NodeTypeInternal::Action)
 }
 
+/// Registers a Condition type node with the factory.
+/// 
+/// **NOTE:** During tree creation, a new node is created using the parameters
+/// given after the node type field. You specified these fields in your node struct
+/// definition. Each time a node is created, the parameters are cloned using `Clone::clone`.
+/// Thus, your parameters must implement `Clone`.
+/// 
+/// # Usage
+/// 
+/// ```ignore
+/// let mut factory = Factory::new();
+/// let arg1 = String::from("hello world");
+/// let arg2 = 10u32;
+/// 
+/// register_condition_node!(factory, "TestNode", TestNode, arg1, arg2);
+/// ```
+#[proc_macro]
+pub fn register_condition_node(input: TokenStream) -> TokenStream {
+    register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Condition }, NodeTypeInternal::Action)
+}
+
 /// Registers an Control type node with the factory.
 /// 
 /// **NOTE:** During tree creation, a new node is created using the parameters
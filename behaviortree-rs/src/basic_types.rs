@@ -106,7 +106,7 @@ pub enum ParsePortDirectionError {
     NoMatch,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PortDirection {
     Input,
     Output,
@@ -418,12 +418,16 @@ impl PortInfo {
         self.description = description
     }
 
+    pub fn description(&self) -> &String {
+        &self.description
+    }
+
     pub fn direction(&self) -> &PortDirection {
         &self.r#type
     }
 }
 
-pub struct Port(String, PortInfo);
+pub struct Port(pub String, pub PortInfo);
 
 impl Port {
     fn create_port(direction: PortDirection, name: &str, description: &str) -> Port {
@@ -480,7 +484,7 @@ impl AttrsToMap for Attributes<'_> {
         for attr in self.into_iter() {
             let attr = attr?;
             let name = String::from_utf8(attr.key.0.into())?;
-            let value = String::from_utf8(attr.value.to_vec())?;
+            let value = attr.unescape_value()?.into_owned();
 
             map.insert(name, value);
         }
@@ -1,7 +1,11 @@
-use std::{any::Any, collections::HashMap, sync::Arc};
-
-use futures::future::BoxFuture;
-use tokio::sync::{Mutex, RwLock};
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        TryLockError,
+    },
+};
 
 use crate::basic_types::{FromString, ParseStr};
 
@@ -85,6 +89,12 @@ where
 ///
 /// If the value type at the key doesn't match `T`, it will _not_ try to
 /// parse a string value. It will just return `None`.
+///
+/// ## Sync and async methods
+///
+/// The Blackboard uses non-async locks, which are never held across an
+/// `.await`, so the `_sync` methods never block on a future and are safe to
+/// call from inside an async runtime. The async methods do the same thing.
 #[derive(Debug, Clone)]
 pub struct Blackboard {
     data: Arc<RwLock<BlackboardData>>,
@@ -120,20 +130,21 @@ impl Blackboard {
         }
     }
 
+    #[allow(dead_code)]
     fn parent(&self) -> Option<Blackboard> {
         self.parent_bb.as_ref().as_ref().cloned()
     }
 
     /// Creates a Blackboard with `parent_bb` as the parent. Returned as a new `BlackboardPtr`.
     pub async fn with_parent(parent_bb: &Blackboard) -> Blackboard {
-        Self::new(Some(parent_bb.clone()))
+        Self::with_parent_sync(parent_bb)
     }
 
     /// Sync version of `with_parent()`
     ///
     /// Creates a Blackboard with `parent_bb` as the parent. Returned as a new `BlackboardPtr`.
     pub fn with_parent_sync(parent_bb: &Blackboard) -> Blackboard {
-        crate::sync::block_on(Self::with_parent(parent_bb))
+        Self::new(Some(parent_bb.clone()))
     }
 
     /// Creates a Blackboard with no parent and returns it as a `BlackboardPtr`.
@@ -152,7 +163,7 @@ impl Blackboard {
     /// the parent Blackboard. Only uses autoremapping if there's no matching
     /// explicit remapping rule.
     pub async fn enable_auto_remapping(&mut self, use_remapping: bool) {
-        self.data.write().await.auto_remapping = use_remapping;
+        self.enable_auto_remapping_sync(use_remapping)
     }
 
     /// Sync version of `enable_auto_remapping()`
@@ -161,17 +172,13 @@ impl Blackboard {
     /// the parent Blackboard. Only uses autoremapping if there's no matching
     /// explicit remapping rule.
     pub fn enable_auto_remapping_sync(&mut self, use_remapping: bool) {
-        crate::sync::block_on(self.enable_auto_remapping(use_remapping))
+        self.write_data().auto_remapping = use_remapping;
     }
 
     /// Adds remapping rule for Blackboard. Maps from `internal` (this Blackboard)
     /// to `external` (a parent Blackboard)
     pub async fn add_subtree_remapping(&mut self, internal: String, external: String) {
-        self.data
-            .write()
-            .await
-            .internal_to_external
-            .insert(internal, external);
+        self.add_subtree_remapping_sync(internal, external)
     }
 
     /// Sync version of add_subtree_remapping
@@ -179,54 +186,92 @@ impl Blackboard {
     /// Adds remapping rule for Blackboard. Maps from `internal` (this Blackboard)
     /// to `external` (a parent Blackboard)
     pub fn add_subtree_remapping_sync(&mut self, internal: String, external: String) {
-        crate::sync::block_on(self.add_subtree_remapping(internal, external));
+        self.write_data()
+            .internal_to_external
+            .insert(internal, external);
     }
 
-    /// Get an Rc to the Entry
-    fn get_entry<'a>(&'a mut self, key: &'a str) -> BoxFuture<Option<EntryPtr>> {
-        Box::pin(async move {
-            let mut blackboard = self.data.write().await;
+    /// Returns the number of entries in this Blackboard, excluding its parents.
+    pub async fn len(&self) -> usize {
+        self.len_sync()
+    }
 
-            // Try to get the key
-            if let Some(entry) = blackboard.storage.get(key) {
-                return Some(Arc::clone(entry));
-            }
-            // Couldn't find key. Try remapping if we have a parent
-            else if let Some(parent_bb) = self.parent_bb.as_mut() {
-                if let Some(new_key) = blackboard.internal_to_external.get(key) {
-                    // Return the value of the parent's `get()`
-                    let parent_entry = parent_bb.get_entry(new_key).await;
-
-                    if let Some(value) = &parent_entry {
-                        blackboard
-                            .storage
-                            .insert(key.to_string(), Arc::clone(value));
-                    }
-
-                    return parent_entry;
-                }
-                // Use auto remapping
-                else if blackboard.auto_remapping {
-                    // Return the value of the parent's `get()`
-                    return parent_bb.get_entry(key).await;
+    /// Sync version of `len()`
+    ///
+    /// Returns the number of entries in this Blackboard, excluding its parents.
+    pub fn len_sync(&self) -> usize {
+        self.read_data().storage.len()
+    }
+
+    /// Returns the number of entries in this Blackboard, excluding its parents,
+    /// or `None` if the Blackboard is currently locked for writing.
+    pub(crate) fn try_len(&self) -> Option<usize> {
+        match self.data.try_read() {
+            Ok(data) => Some(data.storage.len()),
+            Err(TryLockError::Poisoned(e)) => Some(e.into_inner().storage.len()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Returns `true` if this Blackboard has no entries, excluding its parents.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    fn read_data(&self) -> RwLockReadGuard<'_, BlackboardData> {
+        // The data is only modified by simple operations that can't panic
+        // halfway, so it's still valid if a thread panicked with the lock
+        self.data.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_data(&self) -> RwLockWriteGuard<'_, BlackboardData> {
+        self.data.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get an Arc to the Entry
+    pub(crate) fn get_entry(&mut self, key: &str) -> Option<EntryPtr> {
+        // Lock the field directly, so `parent_bb` can still be borrowed
+        let mut blackboard = self.data.write().unwrap_or_else(PoisonError::into_inner);
+
+        // Try to get the key
+        if let Some(entry) = blackboard.storage.get(key) {
+            return Some(Arc::clone(entry));
+        }
+        // Couldn't find key. Try remapping if we have a parent
+        else if let Some(parent_bb) = self.parent_bb.as_mut() {
+            if let Some(new_key) = blackboard.internal_to_external.get(key) {
+                // Return the value of the parent's `get()`
+                let parent_entry = parent_bb.get_entry(new_key);
+
+                if let Some(value) = &parent_entry {
+                    blackboard
+                        .storage
+                        .insert(key.to_string(), Arc::clone(value));
                 }
+
+                return parent_entry;
+            }
+            // Use auto remapping
+            else if blackboard.auto_remapping {
+                // Return the value of the parent's `get()`
+                return parent_bb.get_entry(key);
             }
+        }
 
-            // No matches
-            None
-        })
+        // No matches
+        None
     }
 
     /// Internal method that just tries to get value at key. If the stored
     /// type is not T, return None
-    async fn __get_no_string<T>(&mut self, key: &str) -> Option<T>
+    fn __get_no_string<T>(&mut self, key: &str) -> Option<T>
     where
         T: Any + Clone,
     {
         // Try to get the key
-        if let Some(entry) = self.get_entry(key).await {
+        if let Some(entry) = self.get_entry(key) {
             // Try to downcast directly to T
-            if let Some(value) = entry.lock().await.value.downcast_ref::<T>() {
+            if let Some(value) = lock_entry(&entry).value.downcast_ref::<T>() {
                 return Some(value.clone());
             }
         }
@@ -236,14 +281,14 @@ impl Blackboard {
 
     /// Internal method that tries to get the value at key, but only works
     /// if it's a String/&str, then tries FromString to convert it to T
-    async fn __get_allow_string<T>(&mut self, key: &str) -> Option<T>
+    fn __get_allow_string<T>(&mut self, key: &str) -> Option<T>
     where
         T: Any + Clone + FromString + Send,
     {
         // Try to get the key
-        if let Some(entry) = self.get_entry(key).await {
+        if let Some(entry) = self.get_entry(key) {
             let value = {
-                let entry_lock = entry.lock().await;
+                let entry_lock = lock_entry(&entry);
                 // If value is a String or &str, try to call `FromString` to convert to T
                 if let Some(value) = entry_lock.value.downcast_ref::<String>() {
                     value.to_string()
@@ -259,8 +304,7 @@ impl Blackboard {
             // Try to parse String into T
             if let Ok(value) = <String as ParseStr<T>>::parse_str(&value) {
                 // Update value with the value type instead of just a string
-                let mut t = entry.lock().await;
-                t.value = Box::new(value.clone());
+                lock_entry(&entry).value = Box::new(value.clone());
                 return Some(value);
             }
         }
@@ -287,7 +331,7 @@ impl Blackboard {
     ///         - If `auto_remapping` is enabled, it uses `key` directly
     ///     - Return `None` if none of the above work
     /// - If a value is matched, attempt to coerce the value to `T`. If it couldn't
-    /// be coerced to `T`:
+    ///   be coerced to `T`:
     ///     - If it's a `String` or `&str`, try calling `parse_str()`
     /// - If none of those work, return `None`
     ///
@@ -316,10 +360,7 @@ impl Blackboard {
     where
         T: Any + Clone + FromString + Send,
     {
-        // Try without parsing string first, then try with parsing string
-        self.__get_no_string(key.as_ref())
-            .await
-            .or(self.__get_allow_string(key.as_ref()).await)
+        self.get_sync(key)
     }
 
     /// Sync version of `get<T>`
@@ -342,7 +383,7 @@ impl Blackboard {
     ///         - If `auto_remapping` is enabled, it uses `key` directly
     ///     - Return `None` if none of the above work
     /// - If a value is matched, attempt to coerce the value to `T`. If it couldn't
-    /// be coerced to `T`:
+    ///   be coerced to `T`:
     ///     - If it's a `String` or `&str`, try calling `parse_str()`
     /// - If none of those work, return `None`
     ///
@@ -371,7 +412,9 @@ impl Blackboard {
     where
         T: Any + Clone + FromString + Send,
     {
-        futures::executor::block_on(self.get(key))
+        // Try without parsing string first, then try with parsing string
+        self.__get_no_string(key.as_ref())
+            .or_else(|| self.__get_allow_string(key.as_ref()))
     }
 
     /// Version of `get<T>` that does _not_ try to convert from string if the type
@@ -402,7 +445,7 @@ impl Blackboard {
     where
         T: Any + Clone,
     {
-        self.__get_no_string(key.as_ref()).await
+        self.get_exact_sync(key)
     }
 
     /// Sync version of `get_exact<T>`
@@ -435,7 +478,7 @@ impl Blackboard {
     where
         T: Any + Clone,
     {
-        futures::executor::block_on(self.get_exact(key))
+        self.__get_no_string(key.as_ref())
     }
 
     /// Sets the `value` in the Blackboard at `key`.
@@ -458,19 +501,7 @@ impl Blackboard {
     /// # })
     /// ```
     pub async fn set<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
-        let key = key.as_ref().to_string();
-
-        let mut blackboard = self.data.write().await;
-
-        if let Some(entry) = blackboard.storage.get_mut(&key) {
-            entry.lock().await.value = Box::new(value);
-        } else {
-            drop(blackboard);
-            let entry = self.create_entry(&key).await;
-
-            // Set value of new entry
-            entry.lock().await.value = Box::new(value);
-        }
+        self.set_sync(key, value)
     }
 
     /// Sync version of `set<T>`
@@ -495,52 +526,63 @@ impl Blackboard {
     /// # })
     /// ```
     pub fn set_sync<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
-        futures::executor::block_on(self.set(key, value))
+        let key = key.as_ref().to_string();
+
+        let existing = self.read_data().storage.get(&key).cloned();
+        let entry = match existing {
+            Some(entry) => entry,
+            None => self.create_entry(&key),
+        };
+
+        lock_entry(&entry).value = Box::new(value);
     }
 
-    fn create_entry<'a>(&'a mut self, key: &'a (impl AsRef<str> + Sync)) -> BoxFuture<EntryPtr> {
-        Box::pin(async move {
-            let entry;
+    pub(crate) fn create_entry(&mut self, key: &(impl AsRef<str> + Sync)) -> EntryPtr {
+        let entry;
 
-            let mut blackboard = self.data.write().await;
+        let mut blackboard = self.data.write().unwrap_or_else(PoisonError::into_inner);
 
-            // If the entry already exists
-            if let Some(existing_entry) = blackboard.storage.get(key.as_ref()) {
-                return Arc::clone(existing_entry);
-            }
-            // Use explicit remapping rule
-            else if blackboard.internal_to_external.contains_key(key.as_ref())
-                && self.parent_bb.is_some()
-            {
-                // Safe to unwrap because .contains_key() is true
-                let remapped_key = blackboard.internal_to_external.get(key.as_ref()).unwrap();
-
-                entry = (*self.parent_bb)
-                    .as_mut()
-                    .unwrap()
-                    .create_entry(remapped_key)
-                    .await;
-            }
-            // Use autoremapping
-            else if blackboard.auto_remapping && self.parent_bb.is_some() {
-                entry = (*self.parent_bb).as_mut().unwrap().create_entry(key).await;
-            }
-            // No remapping or no parent blackboard
-            else {
-                // Create an entry with an empty placeholder value
-                entry = Arc::new(Mutex::new(Entry {
-                    value: Box::new(()),
-                }));
-            }
+        // If the entry already exists
+        if let Some(existing_entry) = blackboard.storage.get(key.as_ref()) {
+            return Arc::clone(existing_entry);
+        }
+        // Use explicit remapping rule
+        else if blackboard.internal_to_external.contains_key(key.as_ref())
+            && self.parent_bb.is_some()
+        {
+            // Safe to unwrap because .contains_key() is true
+            let remapped_key = blackboard.internal_to_external.get(key.as_ref()).unwrap();
+
+            entry = (*self.parent_bb)
+                .as_mut()
+                .unwrap()
+                .create_entry(remapped_key);
+        }
+        // Use autoremapping
+        else if blackboard.auto_remapping && self.parent_bb.is_some() {
+            entry = (*self.parent_bb).as_mut().unwrap().create_entry(key);
+        }
+        // No remapping or no parent blackboard
+        else {
+            // Create an entry with an empty placeholder value
+            entry = Arc::new(Mutex::new(Entry {
+                value: Box::new(()),
+            }));
+        }
 
-            blackboard
-                .storage
-                .insert(key.as_ref().to_string(), Arc::clone(&entry));
-            entry
-        })
+        blackboard
+            .storage
+            .insert(key.as_ref().to_string(), Arc::clone(&entry));
+        entry
     }
 }
 
+/// Locks an entry. Values are replaced whole, so an entry is still valid if
+/// a thread panicked with the lock.
+pub(crate) fn lock_entry(entry: &EntryPtr) -> MutexGuard<'_, Entry> {
+    entry.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
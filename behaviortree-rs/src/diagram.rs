@@ -0,0 +1,269 @@
+use std::fmt::Write as _;
+
+use crate::{
+    basic_types::{NodeStatus, NodeType},
+    nodes::TreeNodeBase,
+};
+
+/// How `SubTreeNode`s are drawn in a diagram.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubTreeStyle {
+    /// The SubTree is drawn as a node, connected to the root of the tree
+    /// it references like any other child.
+    #[default]
+    Inline,
+    /// The nodes of the tree referenced by the SubTree are drawn inside a
+    /// cluster (a `subgraph`), labeled with the SubTree.
+    Cluster,
+}
+
+/// Options of `write_tree_to_dot()` and `write_tree_to_mermaid()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiagramOptions {
+    /// Fill every node with a color based on its current `NodeStatus`.
+    /// Idle nodes aren't filled.
+    pub status_colors: bool,
+    pub subtrees: SubTreeStyle,
+}
+
+/// Renders a tree as a Graphviz DOT `digraph`. Nodes are named `n{uid}`
+/// and their shape depends on their `NodeType`:
+///
+/// | `NodeType`  | Shape             |
+/// | ----------- | ----------------- |
+/// | `Control`   | `box`             |
+/// | `Decorator` | `diamond`         |
+/// | `Action`    | `ellipse`         |
+/// | `Condition` | `hexagon`         |
+/// | `SubTree`   | double-lined box  |
+///
+/// # Examples
+///
+/// ```ignore
+/// let options = DiagramOptions {
+///     status_colors: true,
+///     ..Default::default()
+/// };
+/// std::fs::write("tree.dot", tree.to_dot(&options))?;
+/// ```
+pub fn write_tree_to_dot(root: &dyn TreeNodeBase, options: &DiagramOptions) -> String {
+    let mut diagram = Diagram::new(*options);
+    diagram.write_dot_node(root, 1);
+    diagram.edges.sort();
+
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(out, "digraph {} {{", dot_string(root.name()));
+    let _ = writeln!(out, "    node [fontname=\"Helvetica\"];");
+    out.push_str(&diagram.body);
+    for (parent, child) in &diagram.edges {
+        let _ = writeln!(out, "    n{parent} -> n{child};");
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+/// Renders a tree as a Mermaid flowchart, from top to bottom. Nodes are named
+/// `n{uid}` and their shape depends on their `NodeType`:
+///
+/// | `NodeType`  | Shape      |
+/// | ----------- | ---------- |
+/// | `Control`   | rectangle  |
+/// | `Decorator` | rhombus    |
+/// | `Action`    | stadium    |
+/// | `Condition` | hexagon    |
+/// | `SubTree`   | subroutine |
+pub fn write_tree_to_mermaid(root: &dyn TreeNodeBase, options: &DiagramOptions) -> String {
+    let mut diagram = Diagram::new(*options);
+    diagram.write_mermaid_node(root, 1);
+    diagram.edges.sort();
+
+    let mut out = String::from("flowchart TD\n");
+    out.push_str(&diagram.body);
+    for (parent, child) in &diagram.edges {
+        // Writing to a String can't fail
+        let _ = writeln!(out, "    n{parent} --> n{child}");
+    }
+    out.push_str(&diagram.styles);
+
+    out
+}
+
+/// Nodes, clusters and edges of a diagram being written. Edges are written
+/// after every node, so they don't pull nodes into clusters. Sorting them
+/// puts them in depth-first order, since UIDs are assigned in that order.
+struct Diagram {
+    options: DiagramOptions,
+    body: String,
+    edges: Vec<(u16, u16)>,
+    /// Mermaid `style` statements
+    styles: String,
+}
+
+impl Diagram {
+    fn new(options: DiagramOptions) -> Diagram {
+        Self {
+            options,
+            body: String::new(),
+            edges: Vec::new(),
+            styles: String::new(),
+        }
+    }
+
+    fn is_cluster(&self, node: &dyn TreeNodeBase) -> bool {
+        self.options.subtrees == SubTreeStyle::Cluster && node.node_type() == NodeType::SubTree
+    }
+
+    fn fill_color(&self, node: &dyn TreeNodeBase) -> Option<&'static str> {
+        if self.options.status_colors {
+            status_color(node.status())
+        } else {
+            None
+        }
+    }
+
+    /// Writes `node` and its children, and returns the UID of the node the
+    /// parent should be connected to. Clusters aren't nodes, so the parent
+    /// of a SubTree drawn as a cluster is connected to the root of the tree.
+    fn write_dot_node(&mut self, node: &dyn TreeNodeBase, depth: usize) -> Option<u16> {
+        let indent = "    ".repeat(depth);
+        let uid = node.config().uid;
+
+        // Writing to a String can't fail
+        if self.is_cluster(node) {
+            let _ = writeln!(self.body, "{indent}subgraph cluster_{uid} {{");
+            let _ = writeln!(
+                self.body,
+                "{indent}    label={};",
+                dot_string(&label(node, "\n"))
+            );
+            if let Some(color) = self.fill_color(node) {
+                let _ = writeln!(self.body, "{indent}    style=filled;");
+                let _ = writeln!(self.body, "{indent}    fillcolor=\"{color}\";");
+            }
+
+            let child = node
+                .child_nodes()
+                .first()
+                .and_then(|child| self.write_dot_node(&**child, depth + 1));
+
+            let _ = writeln!(self.body, "{indent}}}");
+
+            return child;
+        }
+
+        let shape = match node.node_type() {
+            NodeType::Control => "shape=box",
+            NodeType::Decorator => "shape=diamond",
+            NodeType::Condition => "shape=hexagon",
+            NodeType::SubTree => "shape=box, peripheries=2",
+            NodeType::Action | NodeType::Undefined => "shape=ellipse",
+        };
+        let fill = match self.fill_color(node) {
+            Some(color) => format!(", style=filled, fillcolor=\"{color}\""),
+            None => String::new(),
+        };
+
+        let _ = writeln!(
+            self.body,
+            "{indent}n{uid} [label={}, {shape}{fill}];",
+            dot_string(&label(node, "\n"))
+        );
+
+        for child in node.child_nodes() {
+            if let Some(child) = self.write_dot_node(&**child, depth) {
+                self.edges.push((uid, child));
+            }
+        }
+
+        Some(uid)
+    }
+
+    /// Same as `write_dot_node()`, for Mermaid.
+    fn write_mermaid_node(&mut self, node: &dyn TreeNodeBase, depth: usize) -> Option<u16> {
+        let indent = "    ".repeat(depth);
+        let uid = node.config().uid;
+        let text = mermaid_string(&label(node, "<br/>"));
+
+        // Writing to a String can't fail
+        if self.is_cluster(node) {
+            let _ = writeln!(self.body, "{indent}subgraph cluster_{uid} [{text}]");
+
+            let child = node
+                .child_nodes()
+                .first()
+                .and_then(|child| self.write_mermaid_node(&**child, depth + 1));
+
+            let _ = writeln!(self.body, "{indent}end");
+            if let Some(color) = self.fill_color(node) {
+                let _ = writeln!(self.styles, "    style cluster_{uid} fill:{color}");
+            }
+
+            return child;
+        }
+
+        let (open, close) = match node.node_type() {
+            NodeType::Control => ("[", "]"),
+            NodeType::Decorator => ("{", "}"),
+            NodeType::Condition => ("{{", "}}"),
+            NodeType::SubTree => ("[[", "]]"),
+            NodeType::Action | NodeType::Undefined => ("([", "])"),
+        };
+
+        let _ = writeln!(self.body, "{indent}n{uid}{open}{text}{close}");
+        if let Some(color) = self.fill_color(node) {
+            let _ = writeln!(self.styles, "    style n{uid} fill:{color}");
+        }
+
+        for child in node.child_nodes() {
+            if let Some(child) = self.write_mermaid_node(&**child, depth) {
+                self.edges.push((uid, child));
+            }
+        }
+
+        Some(uid)
+    }
+}
+
+/// Returns the label of a node: its name, followed by its registration ID
+/// on a separate line if it's different. For SubTrees, the ID of the tree
+/// is used instead of the registration ID.
+fn label(node: &dyn TreeNodeBase, line_break: &str) -> String {
+    let config = node.config();
+    let id = match node.node_type() {
+        NodeType::SubTree => config.input_ports.get("ID").cloned(),
+        _ => config.manifest.as_ref().map(|m| m.registration_id.clone()),
+    };
+
+    match id {
+        Some(id) if &id != node.name() => format!("{}{line_break}{id}", node.name()),
+        _ => node.name().clone(),
+    }
+}
+
+fn status_color(status: NodeStatus) -> Option<&'static str> {
+    match status {
+        NodeStatus::Idle => None,
+        NodeStatus::Running => Some("#f9e79f"),
+        NodeStatus::Success => Some("#a9dfbf"),
+        NodeStatus::Failure => Some("#f5b7b1"),
+        NodeStatus::Skipped => Some("#aed6f1"),
+    }
+}
+
+/// Quotes and escapes a DOT string.
+fn dot_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{escaped}\"")
+}
+
+/// Quotes a Mermaid label. Quotes can't be escaped with a backslash, so they
+/// are written as an entity code.
+fn mermaid_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "#quot;"))
+}
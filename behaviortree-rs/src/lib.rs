@@ -149,7 +149,7 @@ use behaviortree_rs::{
 struct DummyActionStruct {}
 
 impl AsyncTick for DummyActionStruct {
-    fn tick(&mut self) -> BoxFuture<Result<NodeStatus, NodeError>> {
+    fn tick(&mut self) -> BoxFuture<'_, Result<NodeStatus, NodeError>> {
         Box::pin(async move {
             // Some implementation
             // ...
@@ -180,9 +180,14 @@ impl AsyncHalt for DummyActionStruct {}
 
 ### `SyncTick`
 
-TODO: Currently doesn't compile. Need to address
+Nodes with the `Sync` runtime are ticked without spawning a task, so a
+`SyncTree` made of them never has to block on a future. On a multi-threaded
+tokio runtime, they run inside `tokio::task::block_in_place()`, so a slow
+`tick()` doesn't hold up the other tasks of the worker thread. On a
+current-thread runtime, they run inline on the executor thread and should
+return quickly.
 
-```ignore
+```rust
 use behaviortree_rs::{
     bt_node,
     nodes::{SyncTick, SyncHalt, NodeStatus, NodeError, PortsList, NodePorts},
@@ -225,11 +230,20 @@ extern crate self as behaviortree_rs;
 
 pub mod basic_types;
 pub mod blackboard;
+pub mod diagram;
+pub mod loggers;
 
 pub mod nodes;
+pub mod source_location;
 
 pub mod macros;
+pub mod scripting;
+pub mod spans;
+pub mod sync;
+pub mod tick_rate;
 pub mod tree;
+pub mod wake_up;
+pub mod xml_writer;
 
 pub mod derive {
     pub use behaviortree_rs_derive::*;
@@ -243,10 +257,3 @@ pub use tree::Factory;
 
 extern crate futures as futures_internal;
 extern crate tokio as tokio_internal;
-
-pub mod sync {
-    pub use futures::{executor::block_on, future::BoxFuture};
-
-    pub use tokio::sync::Mutex;
-    pub use tokio::task::spawn_blocking;
-}
@@ -0,0 +1,307 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use log::error;
+
+use crate::{
+    basic_types::NodeStatus,
+    loggers::StatusChangeLogger,
+    nodes::{NodeResult, TreeNodeBase},
+};
+
+/// A trace in the Chrome Trace Event format, which can be opened in
+/// `chrome://tracing` or [Perfetto](https://ui.perfetto.dev). The ticks of
+/// the nodes are recorded by `TickProfiler`s, one per tree, created with
+/// `ChromeTrace::profiler()`.
+///
+/// Every tree gets its own track, where the tick of every node is a slice
+/// nested inside the tick of its parent.
+///
+/// # Examples
+///
+/// ```ignore
+/// let trace = ChromeTrace::stream_to_file("trace.json")?;
+///
+/// tree.add_logger(trace.profiler("main"));
+/// other_tree.add_logger(trace.profiler("other"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChromeTrace {
+    sink: Arc<Mutex<TraceSink>>,
+}
+
+impl ChromeTrace {
+    /// Writes every event to the file at `path`, as soon as the tick of the
+    /// tree that generated it completes. The file is a valid JSON array once
+    /// the trace and every profiler are dropped, but can also be opened
+    /// while the trace is being recorded.
+    pub fn stream_to_file(path: impl AsRef<Path>) -> io::Result<ChromeTrace> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(b"[")?;
+
+        Ok(Self::new(TraceOutput::Stream {
+            writer,
+            empty: true,
+        }))
+    }
+
+    /// Writes every tick of a tree to its own file in `dir`, named
+    /// `{track}_{tick}.json`, where `tick` counts from 0. `dir` is created
+    /// if it doesn't exist.
+    pub fn per_tick(dir: impl AsRef<Path>) -> io::Result<ChromeTrace> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self::new(TraceOutput::PerTick { dir }))
+    }
+
+    fn new(output: TraceOutput) -> ChromeTrace {
+        Self {
+            sink: Arc::new(Mutex::new(TraceSink {
+                output,
+                next_tid: 0,
+            })),
+        }
+    }
+
+    /// Creates a profiler that records the ticks of a tree to a new track
+    /// named `track`. Attach it with `add_logger()`.
+    pub fn profiler(&self, track: impl Into<String>) -> TickProfiler {
+        let tid = self
+            .sink
+            .lock()
+            .map(|mut sink| {
+                sink.next_tid += 1;
+                sink.next_tid
+            })
+            .unwrap_or_default();
+
+        TickProfiler {
+            sink: self.sink.clone(),
+            tid,
+            track: track.into(),
+            named: false,
+            depth: 0,
+            tick: 0,
+            events: Vec::new(),
+        }
+    }
+
+    /// Flushes the events written to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        match self.sink.lock() {
+            Ok(mut sink) => sink.flush(),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TraceOutput {
+    /// Every event goes to a single JSON array
+    Stream {
+        writer: BufWriter<File>,
+        empty: bool,
+    },
+    /// Every tick goes to its own file
+    PerTick { dir: PathBuf },
+}
+
+#[derive(Debug)]
+struct TraceSink {
+    output: TraceOutput,
+    next_tid: u32,
+}
+
+impl TraceSink {
+    fn write_tick(&mut self, track: &str, tick: u64, events: &[String]) -> io::Result<()> {
+        match &mut self.output {
+            TraceOutput::Stream { writer, empty } => {
+                for event in events {
+                    // Separators go before events, so the file is only
+                    // missing the closing bracket while recording
+                    if !*empty {
+                        writer.write_all(b",")?;
+                    }
+                    writer.write_all(b"\n")?;
+                    writer.write_all(event.as_bytes())?;
+                    *empty = false;
+                }
+
+                Ok(())
+            }
+            TraceOutput::PerTick { dir } => {
+                let mut writer =
+                    BufWriter::new(File::create(dir.join(format!("{track}_{tick}.json")))?);
+                writeln!(writer, "[\n{}\n]", events.join(",\n"))?;
+                writer.flush()
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.output {
+            TraceOutput::Stream { writer, .. } => writer.flush(),
+            TraceOutput::PerTick { .. } => Ok(()),
+        }
+    }
+}
+
+impl Drop for TraceSink {
+    fn drop(&mut self) {
+        if let TraceOutput::Stream { writer, .. } = &mut self.output {
+            if let Err(e) = writer.write_all(b"\n]\n").and_then(|_| writer.flush()) {
+                error!("ChromeTrace couldn't finish the trace: {e}");
+            }
+        }
+    }
+}
+
+/// Records the ticks of the nodes of a tree to a `ChromeTrace`. Events are
+/// buffered until the tick of the root completes, and then written together.
+///
+/// Created with `ChromeTrace::profiler()`.
+#[derive(Debug)]
+pub struct TickProfiler {
+    sink: Arc<Mutex<TraceSink>>,
+    tid: u32,
+    track: String,
+    /// Whether the name of the track was written to the stream
+    named: bool,
+    /// Number of nodes currently ticking
+    depth: usize,
+    /// Number of completed ticks of the root
+    tick: u64,
+    events: Vec<String>,
+}
+
+impl TickProfiler {
+    /// Returns the metadata event that names the track.
+    fn track_name_event(&self) -> String {
+        format!(
+            r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{},"args":{{"name":{}}}}}"#,
+            self.tid,
+            json_string(&self.track)
+        )
+    }
+
+    fn write_events(&mut self) {
+        if self.events.is_empty() {
+            return;
+        }
+
+        let result = match self.sink.lock() {
+            Ok(mut sink) => {
+                let per_tick = matches!(sink.output, TraceOutput::PerTick { .. });
+                if per_tick || !self.named {
+                    self.events.insert(0, self.track_name_event());
+                    self.named = true;
+                }
+
+                sink.write_tick(&self.track, self.tick, &self.events)
+            }
+            Err(_) => Ok(()),
+        };
+
+        if let Err(e) = result {
+            error!("ChromeTrace couldn't write tick {}: {e}", self.tick);
+        }
+
+        self.events.clear();
+        self.tick += 1;
+    }
+}
+
+impl StatusChangeLogger for TickProfiler {
+    fn on_status_change(
+        &mut self,
+        _timestamp: Duration,
+        _node: &dyn TreeNodeBase,
+        _prev_status: NodeStatus,
+        _status: NodeStatus,
+    ) {
+    }
+
+    fn on_tick_start(&mut self, timestamp: Duration, node: &dyn TreeNodeBase) {
+        let id = node
+            .config()
+            .manifest()
+            .map(|m| m.registration_id.clone())
+            .unwrap_or_default();
+
+        self.events.push(format!(
+            r#"{{"name":{},"cat":"{}","ph":"B","ts":{},"pid":1,"tid":{},"args":{{"path":{},"id":{}}}}}"#,
+            json_string(node.name()),
+            node.node_type(),
+            micros(timestamp),
+            self.tid,
+            json_string(node.path()),
+            json_string(&id),
+        ));
+
+        self.depth += 1;
+    }
+
+    fn on_tick_end(&mut self, timestamp: Duration, _node: &dyn TreeNodeBase, result: &NodeResult) {
+        let args = match result {
+            Ok(status) => format!(r#"{{"status":"{status}"}}"#),
+            Err(e) => format!(r#"{{"error":{}}}"#, json_string(&e.to_string())),
+        };
+
+        self.events.push(format!(
+            r#"{{"ph":"E","ts":{},"pid":1,"tid":{},"args":{args}}}"#,
+            micros(timestamp),
+            self.tid,
+        ));
+
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            self.write_events();
+        }
+    }
+
+    fn flush(&mut self) {
+        self.write_events();
+
+        if let Ok(mut sink) = self.sink.lock() {
+            if let Err(e) = sink.flush() {
+                error!("ChromeTrace couldn't flush: {e}");
+            }
+        }
+    }
+}
+
+/// Formats a timestamp in microseconds, the unit of the format.
+fn micros(timestamp: Duration) -> String {
+    let nanos = timestamp.as_nanos();
+    format!("{}.{:03}", nanos / 1000, nanos % 1000)
+}
+
+/// Quotes and escapes `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
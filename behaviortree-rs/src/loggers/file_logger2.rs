@@ -0,0 +1,268 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::error;
+use quick_xml::{events::Event, Reader};
+use thiserror::Error;
+
+use crate::{
+    basic_types::{AttrsToMap, NodeStatus},
+    loggers::StatusChangeLogger,
+    nodes::TreeNodeBase,
+    tree::ParseError,
+    xml_writer,
+};
+
+const HEADER: &[u8] = b"BTCPP4-FileLogger2";
+const PROTOCOL: u8 = 1;
+/// Size of a transition: 6 bytes of timestamp, 2 of UID and 1 of status
+const TRANSITION_SIZE: usize = 9;
+/// Largest relative timestamp that fits in 6 bytes
+const MAX_TIMESTAMP_USEC: u64 = (1 << 48) - 1;
+
+#[derive(Debug, Error)]
+pub enum BtLogError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Couldn't write the tree XML: {0}")]
+    Xml(#[from] ParseError),
+    #[error("Expected a .btlog file, found [{0}]")]
+    InvalidExtension(PathBuf),
+    #[error("Missing FileLogger2 header")]
+    InvalidHeader,
+    #[error("Unsupported FileLogger2 protocol version [{0}]")]
+    UnsupportedProtocol(u8),
+    #[error("Reached EOF of the log unexpectedly")]
+    UnexpectedEof,
+    #[error("Invalid status [{1}] for node with UID [{0}]")]
+    /// `(uid, status)`
+    InvalidStatus(u16, u8),
+}
+
+/// The FileLogger2 writes every status change to a `.btlog` file, in the
+/// binary format of BehaviorTree.CPP's `FileLogger2`, which can be opened in
+/// Groot2. Recorded files can be read back with `BtLog::read()`.
+///
+/// The file starts with the XML of the tree, where every node has a `_uid`
+/// attribute, followed by the timestamped transitions. Writes are buffered;
+/// the buffer is flushed when the logger is removed from the tree or dropped.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut tree = factory.create_sync_tree_from_text(xml, &blackboard)?;
+///
+/// let logger = FileLogger2::new(tree.root(), "run.btlog")?;
+/// tree.add_logger(logger);
+/// ```
+#[derive(Debug)]
+pub struct FileLogger2 {
+    writer: BufWriter<File>,
+    first_timestamp: Duration,
+    failed: bool,
+}
+
+impl FileLogger2 {
+    /// Creates the file at `path`, which must have the `.btlog` extension, and
+    /// writes the header for the tree starting at `root`.
+    pub fn new(root: &dyn TreeNodeBase, path: impl AsRef<Path>) -> Result<FileLogger2, BtLogError> {
+        let path = path.as_ref();
+        if path.extension().and_then(|e| e.to_str()) != Some("btlog") {
+            return Err(BtLogError::InvalidExtension(path.to_path_buf()));
+        }
+
+        let xml = xml_writer::write_tree_to_xml_with_metadata(root)?;
+        let xml_len = i32::try_from(xml.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "tree XML is too large"))?;
+
+        let first_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(HEADER)?;
+        writer.write_all(&[PROTOCOL])?;
+        writer.write_all(&xml_len.to_le_bytes())?;
+        writer.write_all(xml.as_bytes())?;
+        writer.write_all(&(first_timestamp.as_micros() as i64).to_le_bytes())?;
+
+        Ok(Self {
+            writer,
+            first_timestamp,
+            failed: false,
+        })
+    }
+
+    fn write_transition(
+        &mut self,
+        timestamp: Duration,
+        uid: u16,
+        status: &NodeStatus,
+    ) -> io::Result<()> {
+        let usec = (timestamp.saturating_sub(self.first_timestamp).as_micros() as u64)
+            .min(MAX_TIMESTAMP_USEC);
+
+        let mut buffer = [0; TRANSITION_SIZE];
+        buffer[..6].copy_from_slice(&usec.to_le_bytes()[..6]);
+        buffer[6..8].copy_from_slice(&uid.to_le_bytes());
+        buffer[8] = status_to_byte(status);
+
+        self.writer.write_all(&buffer)
+    }
+}
+
+impl StatusChangeLogger for FileLogger2 {
+    fn on_status_change(
+        &mut self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        _prev_status: NodeStatus,
+        status: NodeStatus,
+    ) {
+        // Report only the first error, instead of one per transition
+        if self.failed {
+            return;
+        }
+
+        if let Err(e) = self.write_transition(timestamp, node.config().uid, &status) {
+            error!("FileLogger2 couldn't write transition: {e}");
+            self.failed = true;
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            error!("FileLogger2 couldn't flush: {e}");
+        }
+    }
+}
+
+/// A status change read from a `.btlog` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    /// Time of the change, since `BtLog::first_timestamp`
+    pub timestamp: Duration,
+    pub uid: u16,
+    pub status: NodeStatus,
+}
+
+/// The contents of a `.btlog` file, as written by `FileLogger2`.
+#[derive(Clone, Debug)]
+pub struct BtLog {
+    /// XML of the tree, with a `_uid` attribute on every node
+    pub xml: String,
+    /// Time the recording started, since the Unix epoch
+    pub first_timestamp: Duration,
+    pub transitions: Vec<Transition>,
+}
+
+impl BtLog {
+    /// Reads a `.btlog` file.
+    pub fn read(path: impl AsRef<Path>) -> Result<BtLog, BtLogError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Parses the contents of a `.btlog` file. An incomplete transition at the
+    /// end, e.g. if the process was killed while writing, is ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<BtLog, BtLogError> {
+        let bytes = bytes
+            .strip_prefix(HEADER)
+            .ok_or(BtLogError::InvalidHeader)?;
+
+        let (protocol, bytes) = split(bytes, 1)?;
+        if protocol[0] != PROTOCOL {
+            return Err(BtLogError::UnsupportedProtocol(protocol[0]));
+        }
+
+        let (xml_len, bytes) = split(bytes, 4)?;
+        let xml_len = i32::from_le_bytes(xml_len.try_into().unwrap());
+        let xml_len = usize::try_from(xml_len).map_err(|_| BtLogError::InvalidHeader)?;
+
+        let (xml, bytes) = split(bytes, xml_len)?;
+        let xml = String::from_utf8(xml.to_vec()).map_err(ParseError::from)?;
+
+        let (first_timestamp, bytes) = split(bytes, 8)?;
+        let first_timestamp = i64::from_le_bytes(first_timestamp.try_into().unwrap());
+        let first_timestamp = Duration::from_micros(first_timestamp.max(0) as u64);
+
+        let transitions = bytes
+            .chunks_exact(TRANSITION_SIZE)
+            .map(|chunk| {
+                let mut usec = [0; 8];
+                usec[..6].copy_from_slice(&chunk[..6]);
+                let uid = u16::from_le_bytes([chunk[6], chunk[7]]);
+
+                Ok(Transition {
+                    timestamp: Duration::from_micros(u64::from_le_bytes(usec)),
+                    uid,
+                    status: status_from_byte(chunk[8])
+                        .ok_or(BtLogError::InvalidStatus(uid, chunk[8]))?,
+                })
+            })
+            .collect::<Result<_, BtLogError>>()?;
+
+        Ok(Self {
+            xml,
+            first_timestamp,
+            transitions,
+        })
+    }
+
+    /// Returns the path of every node in the tree, by UID, as read from the
+    /// `_uid` and `_fullpath` attributes of the XML.
+    pub fn node_paths(&self) -> Result<HashMap<u16, String>, BtLogError> {
+        let mut reader = Reader::from_str(&self.xml);
+        let mut paths = HashMap::new();
+
+        loop {
+            match reader.read_event().map_err(ParseError::from)? {
+                Event::Start(e) | Event::Empty(e) => {
+                    let attributes = e.attributes().to_map()?;
+                    let uid = attributes.get("_uid").and_then(|uid| uid.parse().ok());
+
+                    if let (Some(uid), Some(path)) = (uid, attributes.get("_fullpath")) {
+                        paths.insert(uid, path.clone());
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(paths)
+    }
+}
+
+fn split(bytes: &[u8], len: usize) -> Result<(&[u8], &[u8]), BtLogError> {
+    if bytes.len() < len {
+        return Err(BtLogError::UnexpectedEof);
+    }
+
+    Ok(bytes.split_at(len))
+}
+
+fn status_to_byte(status: &NodeStatus) -> u8 {
+    match status {
+        NodeStatus::Idle => 0,
+        NodeStatus::Running => 1,
+        NodeStatus::Success => 2,
+        NodeStatus::Failure => 3,
+        NodeStatus::Skipped => 4,
+    }
+}
+
+fn status_from_byte(byte: u8) -> Option<NodeStatus> {
+    match byte {
+        0 => Some(NodeStatus::Idle),
+        1 => Some(NodeStatus::Running),
+        2 => Some(NodeStatus::Success),
+        3 => Some(NodeStatus::Failure),
+        4 => Some(NodeStatus::Skipped),
+        _ => None,
+    }
+}
@@ -0,0 +1,292 @@
+//! Loggers receive every status change of the nodes of a tree. They are
+//! attached to a tree with `AsyncTree::add_logger()` or `SyncTree::add_logger()`,
+//! and any number of loggers can be attached at once.
+//!
+//! Besides status changes, loggers can observe the ticks of every node, e.g.
+//! to measure how long they take.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{NodeResult, TreeNodeBase, TreeNodePtr},
+};
+
+mod chrome_trace;
+pub use chrome_trace::*;
+mod file_logger2;
+pub use file_logger2::*;
+mod prometheus;
+pub use prometheus::*;
+mod statistics;
+pub use statistics::*;
+mod stdout_logger;
+pub use stdout_logger::*;
+
+/// Receives the status changes of the nodes of a tree.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use behaviortree_rs::{
+///     basic_types::NodeStatus,
+///     loggers::StatusChangeLogger,
+///     nodes::TreeNodeBase,
+/// };
+///
+/// struct PrintLogger;
+///
+/// impl StatusChangeLogger for PrintLogger {
+///     fn on_status_change(
+///         &mut self,
+///         timestamp: Duration,
+///         node: &dyn TreeNodeBase,
+///         prev_status: NodeStatus,
+///         status: NodeStatus,
+///     ) {
+///         println!("[{timestamp:?}] {}: {prev_status} -> {status}", node.path());
+///     }
+/// }
+/// ```
+pub trait StatusChangeLogger: Send {
+    /// Called every time the status of a node changes. `timestamp` is the time
+    /// of the change, since the Unix epoch.
+    fn on_status_change(
+        &mut self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: NodeStatus,
+        status: NodeStatus,
+    );
+
+    /// Called when `node` starts executing a tick, before its pre-conditions
+    /// are checked.
+    fn on_tick_start(&mut self, _timestamp: Duration, _node: &dyn TreeNodeBase) {}
+
+    /// Called when `node` finishes executing a tick, with the result of the tick.
+    /// Ticks of the children of `node` are nested between its
+    /// `on_tick_start()` and `on_tick_end()`.
+    fn on_tick_end(
+        &mut self,
+        _timestamp: Duration,
+        _node: &dyn TreeNodeBase,
+        _result: &NodeResult,
+    ) {
+    }
+
+    /// Writes any buffered output. Called when the logger is removed from its tree.
+    fn flush(&mut self) {}
+}
+
+/// Allows attaching a logger to a tree while keeping a handle to it, e.g. to
+/// read what it collected.
+impl<L: StatusChangeLogger> StatusChangeLogger for Arc<Mutex<L>> {
+    fn on_status_change(
+        &mut self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: NodeStatus,
+        status: NodeStatus,
+    ) {
+        if let Ok(mut logger) = self.lock() {
+            logger.on_status_change(timestamp, node, prev_status, status);
+        }
+    }
+
+    fn on_tick_start(&mut self, timestamp: Duration, node: &dyn TreeNodeBase) {
+        if let Ok(mut logger) = self.lock() {
+            logger.on_tick_start(timestamp, node);
+        }
+    }
+
+    fn on_tick_end(&mut self, timestamp: Duration, node: &dyn TreeNodeBase, result: &NodeResult) {
+        if let Ok(mut logger) = self.lock() {
+            logger.on_tick_end(timestamp, node, result);
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Ok(mut logger) = self.lock() {
+            logger.flush();
+        }
+    }
+}
+
+/// The loggers attached to a tree. Every node of the tree has a handle to
+/// the same list, so it can notify the loggers when its status changes.
+#[derive(Clone, Default)]
+pub struct TreeLoggers {
+    loggers: Arc<Mutex<Vec<Box<dyn StatusChangeLogger>>>>,
+    /// UIDs of the nodes with a tick in progress, outermost first
+    open_ticks: Arc<Mutex<Vec<u16>>>,
+}
+
+impl TreeLoggers {
+    pub fn new() -> TreeLoggers {
+        Self::default()
+    }
+
+    /// Adds a logger to the list.
+    pub fn add(&self, logger: impl StatusChangeLogger + 'static) {
+        if let Ok(mut loggers) = self.loggers.lock() {
+            loggers.push(Box::new(logger));
+        }
+    }
+
+    /// Flushes and removes every logger.
+    pub fn clear(&self) {
+        if let Ok(mut loggers) = self.loggers.lock() {
+            for logger in loggers.iter_mut() {
+                logger.flush();
+            }
+
+            loggers.clear();
+        }
+    }
+
+    /// Flushes every logger.
+    pub fn flush(&self) {
+        if let Ok(mut loggers) = self.loggers.lock() {
+            for logger in loggers.iter_mut() {
+                logger.flush();
+            }
+        }
+    }
+
+    /// Returns the number of loggers.
+    pub fn len(&self) -> usize {
+        self.loggers
+            .lock()
+            .map(|loggers| loggers.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if there aren't any loggers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gives every node of the tree starting at `node` a handle to the loggers.
+    pub(crate) fn attach(&self, node: &mut TreeNodePtr) {
+        node.config_mut().loggers = Some(self.clone());
+
+        for child in node.child_nodes_mut() {
+            self.attach(child);
+        }
+    }
+
+    /// Removes and returns the UIDs of the nodes with a tick in progress,
+    /// outermost first. Used when a tick is dropped before it completes, so
+    /// the loggers never received the end of those ticks.
+    pub(crate) fn take_open_ticks(&self) -> Vec<u16> {
+        self.open_ticks
+            .lock()
+            .map(|mut open_ticks| std::mem::take(&mut *open_ticks))
+            .unwrap_or_default()
+    }
+
+    /// Calls `f` on every logger, with the current time.
+    fn notify(&self, mut f: impl FnMut(&mut dyn StatusChangeLogger, Duration)) {
+        if let Ok(mut loggers) = self.loggers.lock() {
+            // Skip reading the clock when there's nothing to notify
+            if loggers.is_empty() {
+                return;
+            }
+
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+
+            for logger in loggers.iter_mut() {
+                f(&mut **logger, timestamp);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TreeLoggers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeLoggers")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Measures the duration of ticks for loggers. Ticks of children are nested
+/// between the start and end of the tick of their parent, so the start times
+/// are kept in a stack.
+///
+/// Durations are measured with the monotonic clock rather than from the
+/// timestamps passed to the loggers, which come from the system clock and
+/// can jump.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TickTimer {
+    /// Start times of the ticks in progress, outermost first
+    starts: Vec<Instant>,
+}
+
+impl TickTimer {
+    /// Records the start of a tick, from `StatusChangeLogger::on_tick_start()`.
+    pub(crate) fn start(&mut self) {
+        self.starts.push(Instant::now());
+    }
+
+    /// Records the end of the innermost tick in progress, from
+    /// `StatusChangeLogger::on_tick_end()`, and returns its duration.
+    pub(crate) fn end(&mut self) -> Duration {
+        self.starts
+            .pop()
+            .map(|start| start.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if no tick is in progress, e.g. after the end of the
+    /// tick of the root.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.starts.is_empty()
+    }
+}
+
+/// Notifies the loggers attached to `node` that its status changed from
+/// `prev_status`. Called by the automatic implementation of `set_status()`.
+#[doc(hidden)]
+pub fn notify_status_change(node: &dyn TreeNodeBase, prev_status: NodeStatus) {
+    if let Some(loggers) = &node.config().loggers {
+        let status = node.status();
+        loggers.notify(|logger, timestamp| {
+            logger.on_status_change(timestamp, node, prev_status.clone(), status.clone())
+        });
+    }
+}
+
+/// Notifies the loggers attached to `node` that it started a tick. Called by
+/// the automatic implementation of `execute_tick()`.
+#[doc(hidden)]
+pub fn notify_tick_start(node: &dyn TreeNodeBase) {
+    if let Some(loggers) = &node.config().loggers {
+        if let Ok(mut open_ticks) = loggers.open_ticks.lock() {
+            open_ticks.push(node.config().uid);
+        }
+
+        loggers.notify(|logger, timestamp| logger.on_tick_start(timestamp, node));
+    }
+}
+
+/// Notifies the loggers attached to `node` that it finished a tick. Called by
+/// the automatic implementation of `execute_tick()`.
+#[doc(hidden)]
+pub fn notify_tick_end(node: &dyn TreeNodeBase, result: &NodeResult) {
+    if let Some(loggers) = &node.config().loggers {
+        if let Ok(mut open_ticks) = loggers.open_ticks.lock() {
+            open_ticks.pop();
+        }
+
+        loggers.notify(|logger, timestamp| logger.on_tick_end(timestamp, node, result));
+    }
+}
@@ -0,0 +1,405 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(feature = "prometheus-server")]
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::atomic::{AtomicBool, Ordering},
+    thread::JoinHandle,
+};
+
+use crate::{
+    basic_types::NodeStatus,
+    loggers::{StatusChangeLogger, TickTimer},
+    nodes::{NodeResult, TreeNodeBase},
+};
+
+/// Upper bounds of the buckets of the tick duration histograms, in seconds.
+const DURATION_BUCKETS: [f64; 10] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Collects metrics of trees and renders them in the Prometheus text
+/// exposition format. The metrics of a tree are collected by a
+/// `PrometheusLogger` created with `PrometheusExporter::tree_logger()`.
+///
+/// | Metric                                    | Type      | Labels                          |
+/// | ----------------------------------------- | --------- | ------------------------------- |
+/// | `behaviortree_tree_ticks_total`           | counter   | `tree`                          |
+/// | `behaviortree_blackboard_entries`         | gauge     | `tree`                          |
+/// | `behaviortree_node_ticks_total`           | counter   | `tree`, `path`, `uid`, `id`     |
+/// | `behaviortree_node_results_total`         | counter   | `tree`, `path`, `uid`, `result` |
+/// | `behaviortree_node_tick_duration_seconds` | histogram | `tree`, `path`, `uid`           |
+///
+/// `uid` tells apart siblings with the same name, which share a `path`.
+/// `result` is the status returned by a tick in lowercase, or `error`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let exporter = PrometheusExporter::new();
+/// tree.add_logger(exporter.tree_logger("main"));
+///
+/// tree.tick_while_running()?;
+///
+/// let metrics: String = exporter.render();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PrometheusExporter {
+    trees: Arc<Mutex<BTreeMap<String, TreeMetrics>>>,
+}
+
+#[derive(Debug, Default)]
+struct TreeMetrics {
+    ticks: u64,
+    /// Number of entries in the blackboard of the root of the tree, updated
+    /// at the end of every tick of the root
+    blackboard_entries: Option<usize>,
+    /// Keyed by path and UID
+    nodes: BTreeMap<(String, u16), NodeMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct NodeMetrics {
+    id: String,
+    ticks: u64,
+    results: BTreeMap<&'static str, u64>,
+    /// Number of ticks in each bucket of `DURATION_BUCKETS`, not cumulative
+    buckets: [u64; DURATION_BUCKETS.len()],
+    duration_sum: f64,
+}
+
+impl PrometheusExporter {
+    pub fn new() -> PrometheusExporter {
+        Self::default()
+    }
+
+    /// Creates a logger that collects the metrics of a tree, with the label
+    /// `tree` set to `tree`. Attach it with `add_logger()`.
+    pub fn tree_logger(&self, tree: impl Into<String>) -> PrometheusLogger {
+        PrometheusLogger {
+            trees: self.trees.clone(),
+            tree: tree.into(),
+            tick_timer: TickTimer::default(),
+        }
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        if let Ok(trees) = self.trees.lock() {
+            // Writing to a String can't fail
+            let _ = write_metrics(&mut output, &trees);
+        }
+
+        output
+    }
+
+    /// Serves the metrics over HTTP at `addr`, e.g. `"127.0.0.1:9184"`, from
+    /// a background thread. Every `GET` request is answered with `render()`,
+    /// regardless of the path. The server stops when the returned
+    /// `MetricsServer` is dropped.
+    ///
+    /// The endpoint has no authentication or encryption, and exposes the
+    /// names of every tree and node. Bind it to a loopback address such as
+    /// `127.0.0.1`, so only a Prometheus running on the same machine can
+    /// scrape it. To scrape it from another machine, put it behind a proxy
+    /// that handles authentication instead of binding it to `0.0.0.0`.
+    #[cfg(feature = "prometheus-server")]
+    pub fn serve(&self, addr: impl ToSocketAddrs) -> io::Result<MetricsServer> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let exporter = self.clone();
+        let stop_thread = stop.clone();
+        let thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop_thread.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    if let Err(e) = respond(stream, &exporter) {
+                        log::warn!("[behaviortree_rs]: Metrics request failed: {e}");
+                    }
+                }
+            }
+        });
+
+        Ok(MetricsServer {
+            addr,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Collects the metrics of a tree for a `PrometheusExporter`.
+///
+/// Created with `PrometheusExporter::tree_logger()`.
+#[derive(Debug)]
+pub struct PrometheusLogger {
+    trees: Arc<Mutex<BTreeMap<String, TreeMetrics>>>,
+    tree: String,
+    tick_timer: TickTimer,
+}
+
+impl StatusChangeLogger for PrometheusLogger {
+    fn on_status_change(
+        &mut self,
+        _timestamp: Duration,
+        _node: &dyn TreeNodeBase,
+        _prev_status: NodeStatus,
+        _status: NodeStatus,
+    ) {
+    }
+
+    fn on_tick_start(&mut self, _timestamp: Duration, _node: &dyn TreeNodeBase) {
+        self.tick_timer.start();
+    }
+
+    fn on_tick_end(&mut self, _timestamp: Duration, node: &dyn TreeNodeBase, result: &NodeResult) {
+        let duration = self.tick_timer.end().as_secs_f64();
+        let is_root = self.tick_timer.is_idle();
+
+        let Ok(mut trees) = self.trees.lock() else {
+            return;
+        };
+        let tree = trees.entry(self.tree.clone()).or_default();
+
+        if is_root {
+            tree.ticks += 1;
+            // Keep the previous value if a node is still writing to the blackboard
+            if let Some(entries) = node.config().blackboard.try_len() {
+                tree.blackboard_entries = Some(entries);
+            }
+        }
+
+        let metrics = tree
+            .nodes
+            .entry((node.path().clone(), node.config().uid))
+            .or_insert_with(|| NodeMetrics {
+                id: node
+                    .config()
+                    .manifest
+                    .as_ref()
+                    .map(|m| m.registration_id.clone())
+                    .unwrap_or_default(),
+                ..Default::default()
+            });
+
+        let result = match result {
+            Ok(NodeStatus::Idle) => "idle",
+            Ok(NodeStatus::Running) => "running",
+            Ok(NodeStatus::Success) => "success",
+            Ok(NodeStatus::Failure) => "failure",
+            Ok(NodeStatus::Skipped) => "skipped",
+            Err(_) => "error",
+        };
+
+        metrics.ticks += 1;
+        *metrics.results.entry(result).or_default() += 1;
+        metrics.duration_sum += duration;
+        if let Some(bucket) = DURATION_BUCKETS.iter().position(|le| duration <= *le) {
+            metrics.buckets[bucket] += 1;
+        }
+    }
+}
+
+fn write_metrics(out: &mut String, trees: &BTreeMap<String, TreeMetrics>) -> fmt::Result {
+    write_header(
+        out,
+        "behaviortree_tree_ticks_total",
+        "counter",
+        "Number of ticks of the root of the tree.",
+    )?;
+    for (tree, metrics) in trees {
+        writeln!(
+            out,
+            "behaviortree_tree_ticks_total{{tree={}}} {}",
+            label(tree),
+            metrics.ticks
+        )?;
+    }
+
+    write_header(
+        out,
+        "behaviortree_blackboard_entries",
+        "gauge",
+        "Number of entries in the blackboard of the root of the tree.",
+    )?;
+    for (tree, metrics) in trees {
+        if let Some(entries) = metrics.blackboard_entries {
+            writeln!(
+                out,
+                "behaviortree_blackboard_entries{{tree={}}} {}",
+                label(tree),
+                entries
+            )?;
+        }
+    }
+
+    write_header(
+        out,
+        "behaviortree_node_ticks_total",
+        "counter",
+        "Number of ticks of the node.",
+    )?;
+    for (tree, (path, uid), node) in nodes(trees) {
+        writeln!(
+            out,
+            "behaviortree_node_ticks_total{{tree={},path={},uid=\"{uid}\",id={}}} {}",
+            label(tree),
+            label(path),
+            label(&node.id),
+            node.ticks
+        )?;
+    }
+
+    write_header(
+        out,
+        "behaviortree_node_results_total",
+        "counter",
+        "Number of ticks of the node, by result.",
+    )?;
+    for (tree, (path, uid), node) in nodes(trees) {
+        for (result, count) in &node.results {
+            writeln!(
+                out,
+                "behaviortree_node_results_total{{tree={},path={},uid=\"{uid}\",result=\"{result}\"}} {count}",
+                label(tree),
+                label(path),
+            )?;
+        }
+    }
+
+    write_header(
+        out,
+        "behaviortree_node_tick_duration_seconds",
+        "histogram",
+        "Duration of the ticks of the node.",
+    )?;
+    for (tree, (path, uid), node) in nodes(trees) {
+        let labels = format!("tree={},path={},uid=\"{uid}\"", label(tree), label(path));
+
+        let mut cumulative = 0;
+        for (le, count) in DURATION_BUCKETS.iter().zip(node.buckets) {
+            cumulative += count;
+            writeln!(
+                out,
+                "behaviortree_node_tick_duration_seconds_bucket{{{labels},le=\"{le}\"}} {cumulative}"
+            )?;
+        }
+        writeln!(
+            out,
+            "behaviortree_node_tick_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+            node.ticks
+        )?;
+        writeln!(
+            out,
+            "behaviortree_node_tick_duration_seconds_sum{{{labels}}} {}",
+            node.duration_sum
+        )?;
+        writeln!(
+            out,
+            "behaviortree_node_tick_duration_seconds_count{{{labels}}} {}",
+            node.ticks
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(out, "# HELP {name} {help}")?;
+    writeln!(out, "# TYPE {name} {kind}")
+}
+
+/// Iterates over every node of every tree, as `(tree, (path, uid), metrics)`.
+fn nodes(
+    trees: &BTreeMap<String, TreeMetrics>,
+) -> impl Iterator<Item = (&String, &(String, u16), &NodeMetrics)> {
+    trees.iter().flat_map(|(tree, metrics)| {
+        metrics
+            .nodes
+            .iter()
+            .map(move |(key, node)| (tree, key, node))
+    })
+}
+
+/// Quotes and escapes a label value.
+fn label(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{escaped}\"")
+}
+
+/// Serves the metrics of a `PrometheusExporter` over HTTP until dropped.
+///
+/// Created with `PrometheusExporter::serve()`.
+#[cfg(feature = "prometheus-server")]
+#[derive(Debug)]
+pub struct MetricsServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "prometheus-server")]
+impl MetricsServer {
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+#[cfg(feature = "prometheus-server")]
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+
+        // Wake up the thread blocked on accept()
+        let _ = TcpStream::connect(self.addr);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(feature = "prometheus-server")]
+fn respond(stream: TcpStream, exporter: &PrometheusExporter) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Skip the headers
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let (status, body) = if request_line.starts_with("GET ") {
+        ("200 OK", exporter.render())
+    } else {
+        ("405 Method Not Allowed", String::new())
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
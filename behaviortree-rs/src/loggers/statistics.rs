@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    basic_types::NodeStatus,
+    loggers::{StatusChangeLogger, TickTimer},
+    nodes::{NodeResult, TreeNodeBase},
+};
+
+/// Execution statistics of a node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeStatistics {
+    /// Number of calls to `execute_tick()`
+    pub ticks: u64,
+    /// Number of ticks that returned `NodeStatus::Success`
+    pub success_count: u64,
+    /// Number of ticks that returned `NodeStatus::Failure`
+    pub failure_count: u64,
+    /// Number of ticks that returned `NodeStatus::Skipped`
+    pub skip_count: u64,
+    /// Number of ticks that returned an error
+    pub error_count: u64,
+    /// Number of times the node was halted while running, either by its
+    /// parent or by a `_while` condition
+    pub halt_count: u64,
+    /// Number of transitions into `NodeStatus::Running`
+    pub running_count: u64,
+    pub min_tick_duration: Duration,
+    pub max_tick_duration: Duration,
+    /// Sum of the durations of every tick
+    pub total_tick_duration: Duration,
+}
+
+impl NodeStatistics {
+    /// Returns the mean duration of a tick, or zero if the node was never ticked.
+    pub fn mean_tick_duration(&self) -> Duration {
+        if self.ticks == 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_secs_f64(self.total_tick_duration.as_secs_f64() / self.ticks as f64)
+    }
+
+    fn add_tick(&mut self, duration: Duration, result: &NodeResult) {
+        if self.ticks == 0 || duration < self.min_tick_duration {
+            self.min_tick_duration = duration;
+        }
+        self.max_tick_duration = self.max_tick_duration.max(duration);
+        self.total_tick_duration += duration;
+        self.ticks += 1;
+
+        match result {
+            Ok(NodeStatus::Success) => self.success_count += 1,
+            Ok(NodeStatus::Failure) => self.failure_count += 1,
+            Ok(NodeStatus::Skipped) => self.skip_count += 1,
+            Ok(_) => {}
+            Err(_) => self.error_count += 1,
+        }
+    }
+}
+
+/// The TreeStatistics logger collects `NodeStatistics` for every node of a
+/// tree, by path and UID, so siblings with the same path are counted
+/// separately. Clones share the same statistics, so a clone can be attached
+/// to the tree while another is used to query them at any time.
+///
+/// The statistics can be printed as a table with `to_string()`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let statistics = TreeStatistics::new();
+/// tree.add_logger(statistics.clone());
+///
+/// tree.tick_while_running()?;
+///
+/// println!("{statistics}");
+/// let failures = statistics.get("main/Sequence/OpenDoor").unwrap().failure_count;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TreeStatistics {
+    /// Statistics by path, then by UID
+    nodes: Arc<Mutex<BTreeMap<String, BTreeMap<u16, NodeStatistics>>>>,
+    /// Not shared between clones, so they can be attached to different trees
+    tick_timer: TickTimer,
+}
+
+impl TreeStatistics {
+    pub fn new() -> TreeStatistics {
+        Self::default()
+    }
+
+    /// Returns the statistics of the node at `path`, if it was ticked or
+    /// changed status since the statistics were created or reset. If several
+    /// siblings have the same path, because they don't have a `name`
+    /// attribute, the statistics of the first one are returned. Use
+    /// `get_by_uid()` to tell them apart.
+    pub fn get(&self, path: &str) -> Option<NodeStatistics> {
+        self.nodes
+            .lock()
+            .ok()
+            .and_then(|nodes| nodes.get(path)?.values().next().cloned())
+    }
+
+    /// Returns the statistics of the node with the given UID, if it was ticked
+    /// or changed status since the statistics were created or reset.
+    pub fn get_by_uid(&self, uid: u16) -> Option<NodeStatistics> {
+        self.nodes
+            .lock()
+            .ok()
+            .and_then(|nodes| nodes.values().find_map(|by_uid| by_uid.get(&uid).cloned()))
+    }
+
+    /// Returns the statistics of every node, by path and UID.
+    pub fn snapshot(&self) -> BTreeMap<(String, u16), NodeStatistics> {
+        let Ok(nodes) = self.nodes.lock() else {
+            return BTreeMap::new();
+        };
+
+        nodes
+            .iter()
+            .flat_map(|(path, by_uid)| {
+                by_uid
+                    .iter()
+                    .map(|(uid, s)| ((path.clone(), *uid), s.clone()))
+            })
+            .collect()
+    }
+
+    /// Clears the statistics of every node.
+    pub fn reset(&self) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            nodes.clear();
+        }
+    }
+
+    fn update(&self, node: &dyn TreeNodeBase, f: impl FnOnce(&mut NodeStatistics)) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            let by_uid = match nodes.get_mut(node.path()) {
+                Some(by_uid) => by_uid,
+                None => nodes.entry(node.path().clone()).or_default(),
+            };
+
+            f(by_uid.entry(node.config().uid).or_default());
+        }
+    }
+}
+
+impl StatusChangeLogger for TreeStatistics {
+    fn on_status_change(
+        &mut self,
+        _timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: NodeStatus,
+        status: NodeStatus,
+    ) {
+        match (prev_status, status) {
+            (_, NodeStatus::Running) => self.update(node, |s| s.running_count += 1),
+            // A completed node goes to Success or Failure instead
+            (NodeStatus::Running, NodeStatus::Idle | NodeStatus::Skipped) => {
+                self.update(node, |s| s.halt_count += 1)
+            }
+            _ => {}
+        }
+    }
+
+    fn on_tick_start(&mut self, _timestamp: Duration, _node: &dyn TreeNodeBase) {
+        self.tick_timer.start();
+    }
+
+    fn on_tick_end(&mut self, _timestamp: Duration, node: &dyn TreeNodeBase, result: &NodeResult) {
+        let duration = self.tick_timer.end();
+
+        self.update(node, |s| s.add_tick(duration, result));
+    }
+}
+
+impl fmt::Display for TreeStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = self.snapshot();
+        let width = nodes
+            .keys()
+            .map(|(path, _)| path.len())
+            .max()
+            .unwrap_or_default()
+            .max("Path".len());
+
+        writeln!(
+            f,
+            "{:<width$}  {:>5}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>10}  {:>10}  {:>10}",
+            "Path",
+            "UID",
+            "Ticks",
+            "Success",
+            "Failure",
+            "Skipped",
+            "Errors",
+            "Halted",
+            "Running",
+            "Min (ms)",
+            "Mean (ms)",
+            "Max (ms)",
+        )?;
+
+        for ((path, uid), s) in &nodes {
+            writeln!(
+                f,
+                "{path:<width$}  {uid:>5}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>10.3}  {:>10.3}  {:>10.3}",
+                s.ticks,
+                s.success_count,
+                s.failure_count,
+                s.skip_count,
+                s.error_count,
+                s.halt_count,
+                s.running_count,
+                s.min_tick_duration.as_secs_f64() * 1000.0,
+                s.mean_tick_duration().as_secs_f64() * 1000.0,
+                s.max_tick_duration.as_secs_f64() * 1000.0,
+            )?;
+        }
+
+        Ok(())
+    }
+}
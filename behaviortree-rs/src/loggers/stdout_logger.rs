@@ -0,0 +1,88 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{basic_types::NodeStatus, loggers::StatusChangeLogger, nodes::TreeNodeBase};
+
+/// The StdoutLogger prints every status change to stdout, with the time
+/// elapsed since the logger was created, the path of the node and its
+/// previous and new status in color.
+///
+/// Example output:
+///
+/// ```text
+/// [   0.004s] main/Sequence/OpenDoor  IDLE -> SUCCESS
+/// ```
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut tree = factory.create_sync_tree_from_text(xml, &blackboard)?;
+///
+/// tree.add_logger(StdoutLogger::new().show_idle_transitions(false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StdoutLogger {
+    start: Duration,
+    show_idle_transitions: bool,
+}
+
+impl StdoutLogger {
+    pub fn new() -> StdoutLogger {
+        Self {
+            start: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+            show_idle_transitions: true,
+        }
+    }
+
+    /// Sets whether transitions to `NodeStatus::Idle` are printed. Nodes are
+    /// reset to Idle every time their parent completes, so hiding them makes
+    /// the output shorter. Enabled by default.
+    pub fn show_idle_transitions(mut self, show: bool) -> StdoutLogger {
+        self.show_idle_transitions = show;
+        self
+    }
+
+    /// Returns the line printed for a status change, or `None` if the change
+    /// is filtered out.
+    pub fn format_transition(
+        &self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: &NodeStatus,
+        status: &NodeStatus,
+    ) -> Option<String> {
+        if !self.show_idle_transitions && matches!(status, NodeStatus::Idle) {
+            return None;
+        }
+
+        let elapsed = timestamp.saturating_sub(self.start).as_secs_f64();
+
+        Some(format!(
+            "[{elapsed:>9.3}s] {}  {} -> {}",
+            node.path(),
+            prev_status.into_string_color(),
+            status.into_string_color()
+        ))
+    }
+}
+
+impl Default for StdoutLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusChangeLogger for StdoutLogger {
+    fn on_status_change(
+        &mut self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: NodeStatus,
+        status: NodeStatus,
+    ) {
+        if let Some(line) = self.format_transition(timestamp, node, &prev_status, &status) {
+            println!("{line}");
+        }
+    }
+}
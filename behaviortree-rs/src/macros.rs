@@ -1,5 +1,5 @@
 pub use behaviortree_rs_derive::{
-    register_action_node, register_control_node, register_decorator_node,
+    register_action_node, register_condition_node, register_control_node, register_decorator_node,
 };
 
 /// Macro for simplifying implementation of `FromString` for any type that implements `FromStr`.
@@ -1,23 +1,26 @@
-use std::{cell::RefCell, rc::Rc};
-
 use futures::future::BoxFuture;
 
 use crate::nodes::{NodeResult, TreeNodeBase};
 
+mod script;
+pub use script::*;
+mod script_condition;
+pub use script_condition::*;
+
 pub trait ActionNodeBase: TreeNodeBase + ActionNode {}
 
 pub trait ActionNode {
-    fn execute_action_tick(&mut self) -> BoxFuture<NodeResult>;
+    fn execute_action_tick(&mut self) -> BoxFuture<'_, NodeResult>;
 }
 
 pub trait SyncActionNode {}
 
-pub type ActionNodePtr = Rc<RefCell<dyn ActionNodeBase>>;
+pub type ActionNodePtr = Box<dyn ActionNodeBase + Send + Sync>;
 
 pub trait AsyncStatefulActionNode {
-    fn on_start(&mut self) -> BoxFuture<NodeResult>;
-    fn on_running(&mut self) -> BoxFuture<NodeResult>;
-    fn on_halted(&mut self) -> BoxFuture<()> {
+    fn on_start(&mut self) -> BoxFuture<'_, NodeResult>;
+    fn on_running(&mut self) -> BoxFuture<'_, NodeResult>;
+    fn on_halted(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {})
     }
 }
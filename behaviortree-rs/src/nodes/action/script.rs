@@ -0,0 +1,68 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts, NodeResult},
+    scripting::Script,
+};
+
+/// The ScriptNode executes the script in port "code", which can read
+/// and write entries of the `Blackboard`. It always returns SUCCESS,
+/// unless the script can't be executed.
+///
+/// Example:
+///
+/// ```xml
+/// <Script code="counter := 0; target = 'door'" />
+/// ```
+#[bt_node(SyncActionNode)]
+pub struct ScriptNode {
+    #[bt(default)]
+    script: Option<Script>,
+}
+
+impl AsyncTick for ScriptNode {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let script = load_script(&mut self.config, &mut self.script).await?;
+
+            script
+                .execute(&mut self.config.blackboard)
+                .await
+                .map_err(|e| NodeError::ScriptError(script.source().to_string(), e))?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for ScriptNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(input_port!("code"))
+    }
+}
+
+impl AsyncHalt for ScriptNode {}
+
+/// Reads the script in port "code", only parsing it again if it changed
+/// since the last tick.
+pub(crate) async fn load_script<'a>(
+    config: &mut NodeConfig,
+    cached: &'a mut Option<Script>,
+) -> Result<&'a Script, NodeError> {
+    let code: String = config.get_input("code").await?;
+
+    match cached {
+        Some(script) if script.source() == code => {}
+        _ => {
+            let script =
+                Script::parse(&code).map_err(|e| NodeError::ScriptError(code.clone(), e))?;
+            *cached = Some(script);
+        }
+    }
+
+    // Unwrap is safe because the script was just set
+    Ok(cached.as_ref().unwrap())
+}
@@ -0,0 +1,50 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port},
+    nodes::{action::script::load_script, AsyncHalt, AsyncTick, NodeError, NodePorts, NodeResult},
+    scripting::Script,
+};
+
+/// The ScriptConditionNode executes the script in port "code", and
+/// returns SUCCESS if the result is true, FAILURE otherwise.
+///
+/// Example:
+///
+/// ```xml
+/// <ScriptCondition code="battery &gt; 20 &amp;&amp; !charging" />
+/// ```
+#[bt_node(ConditionNode)]
+pub struct ScriptConditionNode {
+    #[bt(default)]
+    script: Option<Script>,
+}
+
+impl AsyncTick for ScriptConditionNode {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let script = load_script(&mut self.config, &mut self.script).await?;
+
+            let result = match script.execute(&mut self.config.blackboard).await {
+                Ok(value) => value.to_bool(),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(true) => Ok(NodeStatus::Success),
+                Ok(false) => Ok(NodeStatus::Failure),
+                Err(e) => Err(NodeError::ScriptError(script.source().to_string(), e)),
+            }
+        })
+    }
+}
+
+impl NodePorts for ScriptConditionNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(input_port!("code"))
+    }
+}
+
+impl AsyncHalt for ScriptConditionNode {}
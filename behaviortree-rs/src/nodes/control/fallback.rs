@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// The FallbackNode is used to try different strategies,
@@ -25,13 +27,13 @@ pub struct FallbackNode {
 }
 
 impl AsyncTick for FallbackNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             if self.status == NodeStatus::Idle {
                 self.all_skipped = true;
             }
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             while self.child_idx < self.children.len() {
                 let cur_child = &mut self.children[self.child_idx];
@@ -81,7 +83,7 @@ impl AsyncTick for FallbackNode {
 impl NodePorts for FallbackNode {}
 
 impl AsyncHalt for FallbackNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.child_idx = 0;
             self.reset_children().await;
@@ -4,7 +4,9 @@ use log::warn;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// IfThenElseNode must have exactly 2 or 3 children. This node is NOT reactive.
@@ -26,7 +28,7 @@ pub struct IfThenElseNode {
 }
 
 impl AsyncTick for IfThenElseNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let children_count = self.children.len();
             // Node should only have 2 or 3 children
@@ -36,7 +38,7 @@ impl AsyncTick for IfThenElseNode {
                 ));
             }
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             if self.child_idx == 0 {
                 let status = self.children[0].execute_tick().await?;
@@ -82,7 +84,7 @@ impl AsyncTick for IfThenElseNode {
 impl NodePorts for IfThenElseNode {}
 
 impl AsyncHalt for IfThenElseNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.child_idx = 0;
             self.reset_children().await;
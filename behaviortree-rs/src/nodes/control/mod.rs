@@ -1,5 +1,3 @@
-use std::{cell::RefCell, rc::Rc};
-
 use crate::nodes::{NodeError, TreeNodeBase, TreeNodePtr};
 
 mod if_then_else;
@@ -24,7 +22,7 @@ pub use while_do_else::*;
 
 pub trait ControlNodeBase: TreeNodeBase + ControlNode {}
 
-pub type ControlNodePtr = Rc<RefCell<dyn ControlNodeBase>>;
+pub type ControlNodePtr = Box<dyn ControlNodeBase + Send + Sync>;
 
 pub trait ControlNode: TreeNodeBase {
     /// Add child to `ControlNode`
@@ -32,9 +30,9 @@ pub trait ControlNode: TreeNodeBase {
     /// Return reference to `Vec` of children nodes
     fn children(&self) -> &Vec<TreeNodePtr>;
     /// Call `halt()` on child at index
-    fn halt_child(&mut self, index: usize) -> BoxFuture<Result<(), NodeError>>;
+    fn halt_child(&mut self, index: usize) -> BoxFuture<'_, Result<(), NodeError>>;
     /// Halt all children at and after index
-    fn halt_children(&mut self, start: usize) -> BoxFuture<Result<(), NodeError>>;
+    fn halt_children(&mut self, start: usize) -> BoxFuture<'_, Result<(), NodeError>>;
     /// Reset status of all child nodes
-    fn reset_children(&mut self) -> BoxFuture<()>;
+    fn reset_children(&mut self) -> BoxFuture<'_, ()>;
 }
@@ -67,7 +67,7 @@ impl ParallelNode {
 }
 
 impl AsyncTick for ParallelNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.success_threshold = self.config_mut().get_input("success_count").await.unwrap();
             self.failure_threshold = self.config_mut().get_input("failure_count").await.unwrap();
@@ -148,7 +148,7 @@ impl NodePorts for ParallelNode {
 }
 
 impl AsyncHalt for ParallelNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_children().await;
         })
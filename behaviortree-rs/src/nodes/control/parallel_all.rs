@@ -43,7 +43,7 @@ impl ParallelAllNode {
 }
 
 impl AsyncTick for ParallelAllNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.failure_threshold = self.config_mut().get_input("max_failures").await?;
 
@@ -117,7 +117,7 @@ impl NodePorts for ParallelAllNode {
 }
 
 impl AsyncHalt for ParallelAllNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_children().await;
         })
@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// The ReactiveFallback is similar to a ParallelNode.
@@ -21,10 +23,10 @@ use crate::{
 pub struct ReactiveFallbackNode {}
 
 impl AsyncTick for ReactiveFallbackNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let mut all_skipped = true;
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             for index in 0..self.children.len() {
                 let cur_child = &mut self.children[index];
@@ -71,7 +73,7 @@ impl AsyncTick for ReactiveFallbackNode {
 impl NodePorts for ReactiveFallbackNode {}
 
 impl AsyncHalt for ReactiveFallbackNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_children().await;
         })
@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// The ReactiveSequence is similar to a ParallelNode.
@@ -24,11 +26,11 @@ pub struct ReactiveSequenceNode {
 }
 
 impl AsyncTick for ReactiveSequenceNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let mut all_skipped = true;
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             for counter in 0..self.children.len() {
                 let child = &mut self.children[counter];
@@ -84,7 +86,7 @@ impl AsyncTick for ReactiveSequenceNode {
 impl NodePorts for ReactiveSequenceNode {}
 
 impl AsyncHalt for ReactiveSequenceNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_children().await;
         })
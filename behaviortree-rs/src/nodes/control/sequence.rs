@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// The SequenceNode is used to tick children in an ordered sequence.
@@ -24,13 +26,13 @@ pub struct SequenceNode {
 }
 
 impl AsyncTick for SequenceNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             if self.status == NodeStatus::Idle {
                 self.all_skipped = true;
             }
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             while self.child_idx < self.children.len() {
                 let cur_child = &mut self.children[self.child_idx];
@@ -72,7 +74,7 @@ impl AsyncTick for SequenceNode {
 impl NodePorts for SequenceNode {}
 
 impl AsyncHalt for SequenceNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.child_idx = 0;
             self.reset_children().await;
@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 /// The SequenceStarNode is used to tick children in an ordered sequence.
 /// If any child returns RUNNING, previous children are not ticked again.
@@ -24,13 +26,13 @@ pub struct SequenceWithMemoryNode {
 }
 
 impl AsyncTick for SequenceWithMemoryNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             if self.status == NodeStatus::Idle {
                 self.all_skipped = true;
             }
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             while self.child_idx < self.children.len() {
                 let cur_child = &mut self.children[self.child_idx];
@@ -78,7 +80,7 @@ impl AsyncTick for SequenceWithMemoryNode {
 impl NodePorts for SequenceWithMemoryNode {}
 
 impl AsyncHalt for SequenceWithMemoryNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.child_idx = 0;
             self.reset_children().await;
@@ -3,7 +3,9 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// WhileDoElse must have exactly 2 or 3 children.
@@ -21,7 +23,7 @@ use crate::{
 pub struct WhileDoElseNode {}
 
 impl AsyncTick for WhileDoElseNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let children_count = self.children.len();
             // Node should only have 2 or 3 children
@@ -31,7 +33,7 @@ impl AsyncTick for WhileDoElseNode {
                 ));
             }
 
-            self.status = NodeStatus::Running;
+            self.set_status(NodeStatus::Running);
 
             let condition_status = self.children[0].execute_tick().await?;
 
@@ -76,7 +78,7 @@ impl AsyncTick for WhileDoElseNode {
 impl NodePorts for WhileDoElseNode {}
 
 impl AsyncHalt for WhileDoElseNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_children().await;
         })
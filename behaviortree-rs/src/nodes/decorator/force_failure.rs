@@ -11,7 +11,7 @@ use crate::{
 pub struct ForceFailureNode {}
 
 impl AsyncTick for ForceFailureNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.set_status(NodeStatus::Running);
 
@@ -31,7 +31,7 @@ impl AsyncTick for ForceFailureNode {
 impl NodePorts for ForceFailureNode {}
 
 impl AsyncHalt for ForceFailureNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_child().await;
         })
@@ -11,7 +11,7 @@ use crate::{
 pub struct ForceSuccessNode {}
 
 impl AsyncTick for ForceSuccessNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.set_status(NodeStatus::Running);
 
@@ -31,7 +31,7 @@ impl AsyncTick for ForceSuccessNode {
 impl NodePorts for ForceSuccessNode {}
 
 impl AsyncHalt for ForceSuccessNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_child().await;
         })
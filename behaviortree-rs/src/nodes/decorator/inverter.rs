@@ -13,7 +13,7 @@ use crate::{
 pub struct InverterNode {}
 
 impl AsyncTick for InverterNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.set_status(NodeStatus::Running);
 
@@ -41,7 +41,7 @@ impl AsyncTick for InverterNode {
 impl NodePorts for InverterNode {}
 
 impl AsyncHalt for InverterNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_child().await;
         })
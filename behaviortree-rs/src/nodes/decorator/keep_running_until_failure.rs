@@ -11,7 +11,7 @@ use crate::{
 pub struct KeepRunningUntilFailureNode {}
 
 impl AsyncTick for KeepRunningUntilFailureNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             self.set_status(NodeStatus::Running);
 
@@ -35,7 +35,7 @@ impl AsyncTick for KeepRunningUntilFailureNode {
 impl NodePorts for KeepRunningUntilFailureNode {}
 
 impl AsyncHalt for KeepRunningUntilFailureNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_child().await;
         })
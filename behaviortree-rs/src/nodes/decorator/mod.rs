@@ -1,5 +1,3 @@
-use std::{cell::RefCell, rc::Rc};
-
 use crate::nodes::{NodeError, TreeNodeBase, TreeNodePtr};
 
 mod force_failure;
@@ -20,7 +18,7 @@ pub use run_once::*;
 
 pub trait DecoratorNodeBase: TreeNodeBase + DecoratorNode {}
 
-pub type DecoratorNodePtr = Rc<RefCell<dyn DecoratorNodeBase>>;
+pub type DecoratorNodePtr = Box<dyn DecoratorNodeBase + Send + Sync>;
 
 pub trait DecoratorNode: TreeNodeBase {
     /// Set child node for `Decorator`
@@ -28,7 +26,7 @@ pub trait DecoratorNode: TreeNodeBase {
     /// Return reference to child
     fn child(&self) -> Result<&TreeNodePtr, NodeError>;
     /// Call `halt()` on child, same as `reset_child()`
-    fn halt_child(&mut self) -> BoxFuture<()>;
+    fn halt_child(&mut self) -> BoxFuture<'_, ()>;
     /// Reset status of child and call `halt()`
-    fn reset_child(&mut self) -> BoxFuture<()>;
+    fn reset_child(&mut self) -> BoxFuture<'_, ()>;
 }
@@ -35,7 +35,7 @@ pub struct RepeatNode {
 }
 
 impl AsyncTick for RepeatNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             // Load num_cycles from the port value
             self.num_cycles = self.config.get_input("num_cycles").await?;
@@ -99,7 +99,7 @@ impl NodePorts for RepeatNode {
 }
 
 impl AsyncHalt for RepeatNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.repeat_count = 0;
             self.reset_child().await;
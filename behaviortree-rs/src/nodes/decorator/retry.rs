@@ -35,7 +35,7 @@ pub struct RetryNode {
 }
 
 impl AsyncTick for RetryNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             // Load num_cycles from the port value
             self.max_attempts = self.config.get_input("num_attempts").await?;
@@ -100,7 +100,7 @@ impl NodePorts for RetryNode {
 }
 
 impl AsyncHalt for RetryNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.try_count = 0;
             self.reset_child().await;
@@ -25,7 +25,7 @@ pub struct RunOnceNode {
 }
 
 impl AsyncTick for RunOnceNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let skip = self.config.get_input("then_skip").await?;
 
@@ -59,7 +59,7 @@ impl NodePorts for RunOnceNode {
 }
 
 impl AsyncHalt for RunOnceNode {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {
             self.reset_child().await;
         })
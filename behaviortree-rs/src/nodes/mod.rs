@@ -1,15 +1,20 @@
 use std::{any::TypeId, collections::HashMap, sync::Arc};
 
 use futures::future::BoxFuture;
+use log::warn;
 use thiserror::Error;
 
 use crate::{
     basic_types::{
-        self, get_remapped_key, FromString, ParseStr, PortDirection, PortValue,
-        PortsRemapping, TreeNodeManifest,
+        self, get_remapped_key, FromString, ParseStr, PortDirection, PortValue, PortsRemapping,
+        TreeNodeManifest,
     },
     blackboard::BlackboardString,
+    loggers::TreeLoggers,
+    scripting::{Script, ScriptError},
+    spans,
     tree::ParseError,
+    wake_up::WakeUpSignal,
     Blackboard,
 };
 
@@ -24,6 +29,9 @@ pub use decorator::{DecoratorNode, DecoratorNodeBase, DecoratorNodePtr};
 pub mod action;
 pub use action::*;
 
+pub mod subtree;
+pub use subtree::SubTreeNode;
+
 // =============================
 // Trait Definitions
 // =============================
@@ -35,6 +43,7 @@ pub trait TreeNodeBase:
     + NodePorts
     + TreeNodeDefaults
     + GetNodeType
+    + NodeChildren
     + ExecuteTick
     + SyncHalt
     + AsyncHalt
@@ -73,7 +82,7 @@ pub trait SyncTick {
 /// the `tick()` is defined as well as the ports, with
 /// `provided_ports()`.
 pub trait AsyncTick {
-    fn tick(&mut self) -> BoxFuture<NodeResult>;
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult>;
 }
 
 /// Trait that defines the `halt()` function, which gets called
@@ -87,7 +96,7 @@ pub trait SyncHalt {
 /// when a node is stopped. This function typically contains any
 /// cleanup code for the node.
 pub trait AsyncHalt {
-    fn halt(&mut self) -> BoxFuture<()> {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
         Box::pin(async move {})
     }
 }
@@ -120,19 +129,22 @@ pub trait RuntimeType {
 /// ```
 pub trait TreeNodeDefaults {
     fn name(&self) -> &String;
+    fn set_name(&mut self, name: String);
     fn path(&self) -> &String;
     fn status(&self) -> NodeStatus;
     fn reset_status(&mut self);
     fn set_status(&mut self, status: NodeStatus);
     fn config(&self) -> &NodeConfig;
     fn config_mut(&mut self) -> &mut NodeConfig;
-    fn into_boxed(self) -> Box<dyn TreeNodeBase>;
+    fn into_boxed(self) -> TreeNodePtr
+    where
+        Self: Sized + Send + Sync;
 }
 
 /// Automatically implemented for all node types. The implementation
 /// differs based on the `NodeType`.
 pub trait ExecuteTick {
-    fn execute_tick(&mut self) -> BoxFuture<NodeResult>;
+    fn execute_tick(&mut self) -> BoxFuture<'_, NodeResult>;
 }
 
 /// TODO
@@ -143,6 +155,14 @@ pub trait GetNodeType {
     fn node_type(&self) -> basic_types::NodeType;
 }
 
+/// Automatically implemented for all node types. Provides access to the
+/// children of a node without knowing its `NodeType`. Action nodes
+/// return an empty slice and decorators return at most one child.
+pub trait NodeChildren {
+    fn child_nodes(&self) -> &[TreeNodePtr];
+    fn child_nodes_mut(&mut self) -> &mut [TreeNodePtr];
+}
+
 // =============================
 // Enum Definitions
 // =============================
@@ -174,26 +194,95 @@ pub enum NodeError {
     LockPoisoned,
     #[error("A tick method was called that should have been unreachable. Please report this.")]
     UnreachableTick,
+    #[error("A node of a SyncTree had to wait inside an async runtime, which would block it. Use an AsyncTree instead.")]
+    WouldBlock,
+    #[error("Error executing script [{0}]: {1}")]
+    /// `(script, error)`
+    ScriptError(String, ScriptError),
 }
 
-/// TODO: Not currently used
-#[derive(Clone, Debug)]
+/// Scripts evaluated before a node is ticked, set with special attributes
+/// in the XML. If the script returns `true` (or `false` for `WhileTrue`), the
+/// node isn't ticked and returns a status instead:
+///
+/// - `_failureIf`: returns Failure
+/// - `_successIf`: returns Success
+/// - `_skipIf`: returns Skipped
+/// - `_while`: returns Skipped if it's `false` before the node starts. If
+///   it becomes `false` while the node is Running, the node is halted.
+///
+/// Except for `_while`, they're only evaluated when the node is Idle or Skipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PreCond {
     FailureIf,
     SuccessIf,
     SkipIf,
     WhileTrue,
-    Count,
 }
 
-/// TODO: Not currently used
-#[derive(Clone, Debug)]
+impl PreCond {
+    /// Every pre-condition, in the order they're evaluated.
+    pub const ALL: [PreCond; 4] = [
+        PreCond::FailureIf,
+        PreCond::SuccessIf,
+        PreCond::SkipIf,
+        PreCond::WhileTrue,
+    ];
+
+    /// Returns the name of the XML attribute for the pre-condition.
+    pub fn attribute_name(&self) -> &'static str {
+        match self {
+            Self::FailureIf => "_failureIf",
+            Self::SuccessIf => "_successIf",
+            Self::SkipIf => "_skipIf",
+            Self::WhileTrue => "_while",
+        }
+    }
+
+    /// Returns the pre-condition set by the XML attribute `name`, if any.
+    pub fn from_attribute_name(name: &str) -> Option<PreCond> {
+        Self::ALL.into_iter().find(|c| c.attribute_name() == name)
+    }
+}
+
+/// Scripts executed after a node is ticked, set with special attributes
+/// in the XML:
+///
+/// - `_onSuccess`: executed when the node returns Success
+/// - `_onFailure`: executed when the node returns Failure
+/// - `_post`: executed when the node returns either Success or Failure
+/// - `_onHalted`: executed when a Running node is halted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PostCond {
     OnHalted,
     OnFailure,
     OnSuccess,
     Always,
-    Count,
+}
+
+impl PostCond {
+    /// Every post-condition.
+    pub const ALL: [PostCond; 4] = [
+        PostCond::OnHalted,
+        PostCond::OnFailure,
+        PostCond::OnSuccess,
+        PostCond::Always,
+    ];
+
+    /// Returns the name of the XML attribute for the post-condition.
+    pub fn attribute_name(&self) -> &'static str {
+        match self {
+            Self::OnHalted => "_onHalted",
+            Self::OnFailure => "_onFailure",
+            Self::OnSuccess => "_onSuccess",
+            Self::Always => "_post",
+        }
+    }
+
+    /// Returns the post-condition set by the XML attribute `name`, if any.
+    pub fn from_attribute_name(name: &str) -> Option<PostCond> {
+        Self::ALL.into_iter().find(|c| c.attribute_name() == name)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -214,13 +303,18 @@ pub struct NodeConfig {
     pub input_ports: PortsRemapping,
     pub output_ports: PortsRemapping,
     pub manifest: Option<Arc<TreeNodeManifest>>,
+    /// Unique ID of the node in its tree, assigned in depth-first order
     pub uid: u16,
-    /// TODO: doesn't show actual path yet
+    /// Path of the node in its tree, made of the tree ID and the names of the
+    /// node and its parents, e.g. `main/Sequence/MyAction`. The name of a node
+    /// is its `name` attribute, or its registration ID if it has none.
     pub path: String,
-    /// TODO: not used
-    _pre_conditions: HashMap<PreCond, String>,
-    /// TODO: not used
-    _post_conditions: HashMap<PostCond, String>,
+    pre_conditions: HashMap<PreCond, Script>,
+    post_conditions: HashMap<PostCond, Script>,
+    /// Loggers of the tree the node belongs to
+    pub(crate) loggers: Option<TreeLoggers>,
+    /// Shared by every node of the tree the node belongs to
+    pub(crate) wake_up: WakeUpSignal,
 }
 
 impl NodeConfig {
@@ -231,9 +325,11 @@ impl NodeConfig {
             output_ports: HashMap::new(),
             manifest: None,
             uid: 1,
-            path: String::from("TODO"),
-            _pre_conditions: HashMap::new(),
-            _post_conditions: HashMap::new(),
+            path: String::new(),
+            pre_conditions: HashMap::new(),
+            post_conditions: HashMap::new(),
+            loggers: None,
+            wake_up: WakeUpSignal::new(),
         }
     }
 
@@ -242,6 +338,19 @@ impl NodeConfig {
         &self.blackboard
     }
 
+    /// Returns the signal that wakes up the tree the node belongs to. Async
+    /// nodes can emit it when they make progress, so the tree is ticked again
+    /// without waiting. See the `wake_up` module.
+    pub fn wake_up_signal(&self) -> WakeUpSignal {
+        self.wake_up.clone()
+    }
+
+    /// Wakes up the tree the node belongs to. Shorthand for
+    /// `wake_up_signal().emit()`.
+    pub fn emit_wake_up_signal(&self) {
+        self.wake_up.emit();
+    }
+
     /// Adds a port to the config based on the direction. Used during XML parsing.
     pub fn add_port(&mut self, direction: PortDirection, name: String, value: String) {
         match direction {
@@ -279,15 +388,81 @@ impl NodeConfig {
         let _ = self.manifest.insert(manifest);
     }
 
+    /// Returns the script of a pre-condition, if it's set.
+    pub fn pre_condition(&self, condition: PreCond) -> Option<&Script> {
+        self.pre_conditions.get(&condition)
+    }
+
+    /// Sets the script of a pre-condition. Used during XML parsing.
+    pub fn set_pre_condition(&mut self, condition: PreCond, script: Script) {
+        self.pre_conditions.insert(condition, script);
+    }
+
+    /// Returns the script of a post-condition, if it's set.
+    pub fn post_condition(&self, condition: PostCond) -> Option<&Script> {
+        self.post_conditions.get(&condition)
+    }
+
+    /// Sets the script of a post-condition. Used during XML parsing.
+    pub fn set_post_condition(&mut self, condition: PostCond, script: Script) {
+        self.post_conditions.insert(condition, script);
+    }
+
+    /// Executes the script of a pre-condition. Returns `None` if it isn't set.
+    async fn run_pre_condition(&mut self, condition: PreCond) -> Result<Option<bool>, NodeError> {
+        match self.pre_conditions.get(&condition) {
+            Some(script) => {
+                let result = match script.execute(&mut self.blackboard).await {
+                    Ok(value) => value.to_bool(),
+                    Err(e) => Err(e),
+                };
+
+                result
+                    .map(Some)
+                    .map_err(|e| NodeError::ScriptError(script.source().to_string(), e))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Executes the script of a post-condition, if it's set.
+    async fn run_post_condition(&mut self, condition: PostCond) -> Result<(), NodeError> {
+        if let Some(script) = self.post_conditions.get(&condition) {
+            script
+                .execute(&mut self.blackboard)
+                .await
+                .map_err(|e| NodeError::ScriptError(script.source().to_string(), e))?;
+        }
+
+        Ok(())
+    }
+
     /// Returns the value of the input port at the `port` key as a `Result<T, NodeError>`.
     /// The value is `Err` in the following situations:
     /// - The port wasn't found at that key
     /// - `T` doesn't match the type of the stored value
     /// - If a default value is needed (value is empty), couldn't parse default value
     /// - If a remapped key (e.g. a port value of `"{foo}"` references the blackboard
-    /// key `"foo"`), blackboard entry wasn't found or couldn't be read as `T`
+    ///   key `"foo"`), blackboard entry wasn't found or couldn't be read as `T`
     /// - If port value is a string, couldn't convert it to `T` using `parse_str()`.
     pub async fn get_input<T>(&mut self, port: &str) -> Result<T, NodeError>
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        self.get_input_sync(port)
+    }
+
+    /// Sync version of `get_input<T>`
+    ///
+    /// Returns the value of the input port at the `port` key as a `Result<T, NodeError>`.
+    /// The value is `Err` in the following situations:
+    /// - The port wasn't found at that key
+    /// - `T` doesn't match the type of the stored value
+    /// - If a default value is needed (value is empty), couldn't parse default value
+    /// - If a remapped key (e.g. a port value of `"{foo}"` references the blackboard
+    ///   key `"foo"`), blackboard entry wasn't found or couldn't be read as `T`
+    /// - If port value is a string, couldn't convert it to `T` using `parse_str()`.
+    pub fn get_input_sync<T>(&mut self, port: &str) -> Result<T, NodeError>
     where
         T: FromString + Clone + Send + 'static,
     {
@@ -311,7 +486,7 @@ impl NodeConfig {
                 } else {
                     match get_remapped_key(port, val) {
                         // Value is a Blackboard pointer
-                        Some(key) => match self.blackboard.get::<T>(&key).await {
+                        Some(key) => match self.blackboard.get_sync::<T>(&key) {
                             Some(val) => Ok(val),
                             None => Err(NodeError::BlackboardError(key)),
                         },
@@ -331,23 +506,23 @@ impl NodeConfig {
         }
     }
 
-    /// Sync version of `get_input<T>`
+    /// Sets `value` into the blackboard. The key is based on the value provided
+    /// to the port at `port`.
     ///
-    /// Returns the value of the input port at the `port` key as a `Result<T, NodeError>`.
-    /// The value is `Err` in the following situations:
-    /// - The port wasn't found at that key
-    /// - `T` doesn't match the type of the stored value
-    /// - If a default value is needed (value is empty), couldn't parse default value
-    /// - If a remapped key (e.g. a port value of `"{foo}"` references the blackboard
-    /// key `"foo"`), blackboard entry wasn't found or couldn't be read as `T`
-    /// - If port value is a string, couldn't convert it to `T` using `parse_str()`.
-    pub fn get_input_sync<T>(&mut self, port: &str) -> Result<T, NodeError>
+    /// # Examples
+    ///
+    /// - Port value: `"="`: uses the port name as the blackboard key
+    /// - `"foo"` uses `"foo"` as the blackboard key
+    /// - `"{foo}"` uses `"foo"` as the blackboard key
+    pub async fn set_output<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
     where
-        T: FromString + Clone + Send + 'static,
+        T: Clone + Send + 'static,
     {
-        futures::executor::block_on(self.get_input(port))
+        self.set_output_sync(port, value)
     }
 
+    /// Sync version of `set_output<T>`
+    ///
     /// Sets `value` into the blackboard. The key is based on the value provided
     /// to the port at `port`.
     ///
@@ -356,7 +531,7 @@ impl NodeConfig {
     /// - Port value: `"="`: uses the port name as the blackboard key
     /// - `"foo"` uses `"foo"` as the blackboard key
     /// - `"{foo}"` uses `"foo"` as the blackboard key
-    pub async fn set_output<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
+    pub fn set_output_sync<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
     where
         T: Clone + Send + 'static,
     {
@@ -370,30 +545,89 @@ impl NodeConfig {
                     },
                 };
 
-                self.blackboard.set(blackboard_key, value).await;
+                self.blackboard.set_sync(blackboard_key, value);
 
                 Ok(())
             }
             None => Err(NodeError::PortError(port.to_string())),
         }
     }
+}
 
-    /// Sync version of `set_output<T>`
-    ///
-    /// Sets `value` into the blackboard. The key is based on the value provided
-    /// to the port at `port`.
-    ///
-    /// # Examples
-    ///
-    /// - Port value: `"="`: uses the port name as the blackboard key
-    /// - `"foo"` uses `"foo"` as the blackboard key
-    /// - `"{foo}"` uses `"foo"` as the blackboard key
-    pub async fn set_output_sync<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
-    where
-        T: Clone + Send + 'static,
-    {
-        futures::executor::block_on(self.set_output(port, value))
+/// Evaluates the pre-conditions of `node` before it's ticked. Returns the status
+/// the node should return instead of being ticked, if any. Called by the
+/// automatic implementation of `ExecuteTick`.
+pub async fn check_pre_conditions<N>(node: &mut N) -> Result<Option<NodeStatus>, NodeError>
+where
+    N: TreeNodeDefaults + AsyncHalt + Send + ?Sized,
+{
+    let status = node.status();
+
+    for condition in PreCond::ALL {
+        if matches!(status, NodeStatus::Idle | NodeStatus::Skipped) {
+            match node.config_mut().run_pre_condition(condition).await? {
+                Some(true) => match condition {
+                    PreCond::FailureIf => return Ok(Some(NodeStatus::Failure)),
+                    PreCond::SuccessIf => return Ok(Some(NodeStatus::Success)),
+                    PreCond::SkipIf => return Ok(Some(NodeStatus::Skipped)),
+                    PreCond::WhileTrue => {}
+                },
+                Some(false) if condition == PreCond::WhileTrue => {
+                    return Ok(Some(NodeStatus::Skipped))
+                }
+                _ => {}
+            }
+        } else if status == NodeStatus::Running && condition == PreCond::WhileTrue {
+            // Halt the node if the condition stops being true
+            if node.config_mut().run_pre_condition(condition).await? == Some(false) {
+                halt_node(node).await;
+                return Ok(Some(NodeStatus::Skipped));
+            }
+        }
     }
+
+    Ok(None)
+}
+
+/// Executes the post-conditions of `node` after it returned `status`. Called by
+/// the automatic implementation of `ExecuteTick`.
+pub async fn check_post_conditions<N>(node: &mut N, status: &NodeStatus) -> Result<(), NodeError>
+where
+    N: TreeNodeDefaults + Send + ?Sized,
+{
+    let config = node.config_mut();
+
+    match status {
+        NodeStatus::Success => config.run_post_condition(PostCond::OnSuccess).await?,
+        NodeStatus::Failure => config.run_post_condition(PostCond::OnFailure).await?,
+        _ => return Ok(()),
+    }
+
+    config.run_post_condition(PostCond::Always).await
+}
+
+/// Halts `node`, then executes its `_onHalted` script if it has one. Parent
+/// nodes should use this instead of calling `halt()` on their children directly.
+pub async fn halt_node<N>(node: &mut N)
+where
+    N: TreeNodeDefaults + AsyncHalt + Send + ?Sized,
+{
+    let span = spans::halt_span(node);
+
+    spans::instrument(span.clone(), async {
+        AsyncHalt::halt(node).await;
+
+        if let Err(e) = node
+            .config_mut()
+            .run_post_condition(PostCond::OnHalted)
+            .await
+        {
+            warn!("[behaviortree_rs]: {}: {e}", node.path());
+        }
+    })
+    .await;
+
+    spans::record_status(&span, node);
 }
 
 impl Clone for Box<dyn PortValue> {
@@ -0,0 +1,138 @@
+use behaviortree_rs_derive::TreeNodeDefaults;
+use futures::future::BoxFuture;
+use log::debug;
+
+use crate::{
+    basic_types::{NodeStatus, NodeType},
+    loggers::{notify_tick_end, notify_tick_start},
+    nodes::{
+        check_post_conditions, check_pre_conditions, halt_node, AsyncHalt, AsyncTick, ExecuteTick,
+        GetNodeType, NodeChildren, NodeConfig, NodeError, NodePorts, NodeResult, SyncHalt,
+        SyncTick, TreeNodeDefaults, TreeNodePtr,
+    },
+    spans,
+};
+
+/// The SubTreeNode marks the boundary between a tree and one of the
+/// trees it references with `<SubTree ID="..."/>`. Every instantiated
+/// tree, including the main tree, has a SubTreeNode as its root.
+///
+/// The node is transparent during execution: it ticks the root of
+/// the subtree and returns its status.
+///
+/// The attributes of the `<SubTree>` tag (`ID`, `_autoremap` and any
+/// port remapping) are stored as input ports in the `NodeConfig`, so
+/// they can be read back with `get_input::<String>("ID")`.
+#[derive(Debug, TreeNodeDefaults)]
+pub struct SubTreeNode {
+    pub name: String,
+    pub config: NodeConfig,
+    pub status: NodeStatus,
+    pub child: Option<TreeNodePtr>,
+}
+
+impl SubTreeNode {
+    pub fn new(name: impl AsRef<str>, config: NodeConfig) -> SubTreeNode {
+        Self {
+            name: name.as_ref().to_string(),
+            config,
+            status: NodeStatus::Idle,
+            child: None,
+        }
+    }
+
+    /// Returns the ID of the tree referenced by this node.
+    pub fn subtree_id(&self) -> Option<&String> {
+        self.config.input_ports.get("ID")
+    }
+
+    async fn reset_child(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            if matches!(child.status(), NodeStatus::Running) {
+                halt_node(&mut **child).await;
+            }
+
+            child.reset_status();
+        }
+    }
+}
+
+impl AsyncTick for SubTreeNode {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let child = self.child.as_mut().ok_or(NodeError::ChildMissing)?;
+
+            let child_status = child.execute_tick().await?;
+
+            if child_status.is_completed() {
+                self.reset_child().await;
+            }
+
+            Ok(child_status)
+        })
+    }
+}
+
+impl ExecuteTick for SubTreeNode {
+    fn execute_tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        let span = spans::tick_span(self);
+
+        Box::pin(spans::instrument_tick(span, async move {
+            notify_tick_start(self);
+
+            let result = async {
+                let status = match check_pre_conditions(self).await? {
+                    Some(status) => status,
+                    None => {
+                        debug!("[behaviortree_rs]: {}::tick()", self.path());
+                        AsyncTick::tick(self).await?
+                    }
+                };
+
+                check_post_conditions(self, &status).await?;
+                self.set_status(status.clone());
+
+                Ok(status)
+            }
+            .await;
+
+            notify_tick_end(self, &result);
+
+            result
+        }))
+    }
+}
+
+impl SyncTick for SubTreeNode {
+    fn tick(&mut self) -> NodeResult {
+        Err(NodeError::UnreachableTick)
+    }
+}
+
+impl NodePorts for SubTreeNode {}
+
+impl AsyncHalt for SubTreeNode {
+    fn halt(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
+
+impl SyncHalt for SubTreeNode {}
+
+impl GetNodeType for SubTreeNode {
+    fn node_type(&self) -> NodeType {
+        NodeType::SubTree
+    }
+}
+
+impl NodeChildren for SubTreeNode {
+    fn child_nodes(&self) -> &[TreeNodePtr] {
+        self.child.as_slice()
+    }
+
+    fn child_nodes_mut(&mut self) -> &mut [TreeNodePtr] {
+        self.child.as_mut_slice()
+    }
+}
@@ -0,0 +1,123 @@
+use crate::scripting::ScriptError;
+
+/// Operators recognized by the lexer. Longer operators must come before
+/// any operator that is a prefix of them.
+const OPERATORS: &[&str] = &[
+    ":=", "+=", "-=", "*=", "/=", "==", "!=", "<=", ">=", "&&", "||", "..", "=", "<", ">", "!",
+    "+", "-", "*", "/", "(", ")", ";",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Bool(bool),
+    Identifier(String),
+    Operator(&'static str),
+}
+
+impl Token {
+    /// Describes the token in error messages.
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Integer(value) => format!("number [{value}]"),
+            Self::Real(value) => format!("number [{value}]"),
+            Self::String(value) => format!("string ['{value}']"),
+            Self::Bool(value) => format!("[{value}]"),
+            Self::Identifier(name) => format!("identifier [{name}]"),
+            Self::Operator(op) => format!("[{op}]"),
+        }
+    }
+}
+
+/// Splits a script into tokens. Each token is returned with the position
+/// (in characters) where it starts.
+pub(crate) fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let start = pos;
+
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let token = if c.is_ascii_digit()
+            || (c == '.' && chars.get(pos + 1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            let is_hex = c == '0' && matches!(chars.get(pos + 1), Some('x' | 'X'));
+
+            while let Some(&next) = chars.get(pos) {
+                let is_exponent_sign =
+                    matches!(next, '+' | '-') && !is_hex && matches!(chars[pos - 1], 'e' | 'E');
+                // `..` is the concatenation operator, not part of the number
+                let is_decimal_point = next == '.' && chars.get(pos + 1) != Some(&'.');
+
+                if next.is_ascii_alphanumeric() || is_decimal_point || is_exponent_sign {
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+
+            parse_number(&chars[start..pos].iter().collect::<String>())?
+        } else if c.is_alphabetic() || c == '_' || c == '@' {
+            while pos < chars.len()
+                && (chars[pos].is_alphanumeric() || chars[pos] == '_' || chars[pos] == '@')
+            {
+                pos += 1;
+            }
+
+            match chars[start..pos].iter().collect::<String>().as_str() {
+                "true" => Token::Bool(true),
+                "false" => Token::Bool(false),
+                name => Token::Identifier(name.to_string()),
+            }
+        } else if c == '\'' || c == '"' {
+            pos += 1;
+            while pos < chars.len() && chars[pos] != c {
+                pos += 1;
+            }
+
+            if pos == chars.len() {
+                return Err(ScriptError::UnterminatedString(start));
+            }
+
+            pos += 1;
+            Token::String(chars[start + 1..pos - 1].iter().collect())
+        } else {
+            let rest: String = chars[pos..].iter().take(2).collect();
+
+            match OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => {
+                    pos += op.chars().count();
+                    Token::Operator(op)
+                }
+                None => return Err(ScriptError::UnexpectedCharacter(c, start)),
+            }
+        };
+
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+fn parse_number(text: &str) -> Result<Token, ScriptError> {
+    let invalid = || ScriptError::InvalidNumber(text.to_string());
+
+    if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16)
+            .map(Token::Integer)
+            .map_err(|_| invalid())
+    } else if text.contains(['.', 'e', 'E']) {
+        text.parse().map(Token::Real).map_err(|_| invalid())
+    } else {
+        text.parse().map(Token::Integer).map_err(|_| invalid())
+    }
+}
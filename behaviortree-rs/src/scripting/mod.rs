@@ -0,0 +1,331 @@
+//! A small scripting language that reads and writes the `Blackboard`, compatible
+//! with the scripts of BehaviorTree.CPP. Scripts are used by the pre- and
+//! post-condition attributes of nodes, such as `_skipIf` and `_onSuccess`.
+//!
+//! A script is a list of statements separated by `;`. Each statement is either an
+//! assignment or an expression:
+//!
+//! ```text
+//! counter := 0; ready = true
+//! counter += 1; ratio := counter / 2.0
+//! ready && counter >= 3
+//! ```
+//!
+//! - Literals: integers (`42`, `0xFF`), reals (`3.14`), strings (`'hello'`) and
+//!   booleans (`true`, `false`)
+//! - Any other identifier reads the `Blackboard` entry with that name
+//! - `:=` assigns a value, creating the entry if it doesn't exist. `=` only
+//!   assigns to an existing entry, and returns an error otherwise. `+=`, `-=`,
+//!   `*=` and `/=` update an existing entry.
+//! - Arithmetic: `+`, `-`, `*`, `/` and negation (`-value`)
+//! - String concatenation: `'robot_' .. id`
+//! - Comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=`
+//! - Boolean logic: `&&`, `||`, `!`
+//!
+//! Operators follow the usual precedence, from lowest to highest: `||`, `&&`,
+//! equality, comparisons, `..`, `+`/`-` and `*`/`/`. Use parentheses to
+//! group expressions.
+//!
+//! Strings that contain numbers can be used in arithmetic and comparisons, so
+//! values set from XML ports work as expected. Integer arithmetic produces
+//! integers, unless a division isn't exact or the result overflows. When
+//! assigning to an existing entry, the value is converted to the type already
+//! stored in the entry.
+//!
+//! Scripts are parsed once with `Script::parse()`, and can then be executed
+//! any number of times.
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+use crate::blackboard::{lock_entry, Blackboard};
+
+mod lexer;
+mod parser;
+mod value;
+
+use parser::{AssignOp, BinaryOp, Expr, UnaryOp};
+pub use value::ScriptValue;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum ScriptError {
+    #[error("Script doesn't contain any statements")]
+    Empty,
+    #[error("Unexpected character '{0}' at position {1}")]
+    /// `(character, position)`
+    UnexpectedCharacter(char, usize),
+    #[error("String starting at position {0} is missing its closing quote")]
+    UnterminatedString(usize),
+    #[error("Invalid number [{0}]")]
+    InvalidNumber(String),
+    #[error("Unexpected {0} at position {1}, expected {2}")]
+    /// `(token, position, expected)`
+    UnexpectedToken(String, usize, String),
+    #[error("Unexpected end of script, expected {0}")]
+    UnexpectedEnd(String),
+    #[error("Blackboard entry [{0}] doesn't exist")]
+    UnknownEntry(String),
+    #[error("Blackboard entry [{0}] doesn't exist. Use := to create it")]
+    AssignToUnknownEntry(String),
+    #[error("Blackboard entry [{0}] has a type that can't be used in scripts")]
+    UnsupportedType(String),
+    #[error("Can't convert [{0}] to {1}")]
+    /// `(value, type)`
+    InvalidConversion(String, &'static str),
+    #[error("Operator [{0}] can't be applied to {1} and {2}")]
+    /// `(operator, lhs_type, rhs_type)`
+    InvalidOperands(&'static str, &'static str, &'static str),
+    #[error("Operator [-] can't be applied to {0}")]
+    /// `(type)`
+    InvalidNegation(&'static str),
+    #[error("Division by zero")]
+    DivisionByZero,
+}
+
+/// A parsed script, which can be executed on a `Blackboard`.
+///
+/// # Examples
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use behaviortree_rs::{scripting::{Script, ScriptValue}, Blackboard};
+///
+/// let mut blackboard = Blackboard::create();
+///
+/// let script = Script::parse("value := 3; value >= 2").unwrap();
+///
+/// assert_eq!(script.execute(&mut blackboard).await, Ok(ScriptValue::Bool(true)));
+/// assert_eq!(blackboard.get::<i64>("value").await, Some(3));
+/// # })
+/// ```
+#[derive(Debug, Clone)]
+pub struct Script {
+    source: String,
+    statements: Vec<Expr>,
+}
+
+impl Script {
+    /// Parses `source` into a `Script`.
+    pub fn parse(source: impl AsRef<str>) -> Result<Script, ScriptError> {
+        let source = source.as_ref();
+
+        Ok(Self {
+            source: source.to_string(),
+            statements: parser::parse(source)?,
+        })
+    }
+
+    /// Returns the text the script was parsed from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Executes every statement of the script, and returns the value of the last one.
+    pub async fn execute(&self, blackboard: &mut Blackboard) -> Result<ScriptValue, ScriptError> {
+        self.execute_sync(blackboard)
+    }
+
+    /// Sync version of `execute()`
+    ///
+    /// Executes every statement of the script, and returns the value of the last one.
+    /// Doesn't block on any future, so it can be called from an async context.
+    pub fn execute_sync(&self, blackboard: &mut Blackboard) -> Result<ScriptValue, ScriptError> {
+        let mut value = None;
+
+        for statement in self.statements.iter() {
+            value = Some(evaluate(statement, blackboard)?);
+        }
+
+        // Parsing fails if there are no statements
+        value.ok_or(ScriptError::Empty)
+    }
+}
+
+fn evaluate(expr: &Expr, blackboard: &mut Blackboard) -> Result<ScriptValue, ScriptError> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Variable(name) => read_entry(blackboard, name),
+        Expr::Unary(op, expr) => {
+            let value = evaluate(expr, blackboard)?;
+
+            match op {
+                UnaryOp::Not => Ok(ScriptValue::Bool(!value.to_bool()?)),
+                UnaryOp::Negate => match value.to_number() {
+                    Some(ScriptValue::Integer(value)) => Ok(value
+                        .checked_neg()
+                        .map(ScriptValue::Integer)
+                        .unwrap_or(ScriptValue::Real(-(value as f64)))),
+                    Some(ScriptValue::Real(value)) => Ok(ScriptValue::Real(-value)),
+                    _ => Err(ScriptError::InvalidNegation(value.type_name())),
+                },
+            }
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = evaluate(lhs, blackboard)?;
+
+            // Short-circuit boolean operators
+            match op {
+                BinaryOp::And if !lhs.to_bool()? => return Ok(ScriptValue::Bool(false)),
+                BinaryOp::Or if lhs.to_bool()? => return Ok(ScriptValue::Bool(true)),
+                _ => {}
+            }
+
+            let rhs = evaluate(rhs, blackboard)?;
+
+            apply_binary(*op, lhs, rhs)
+        }
+        Expr::Assign(name, op, expr) => {
+            let value = evaluate(expr, blackboard)?;
+
+            write_entry(blackboard, name, *op, value.clone())?;
+
+            Ok(value)
+        }
+    }
+}
+
+fn apply_binary(
+    op: BinaryOp,
+    lhs: ScriptValue,
+    rhs: ScriptValue,
+) -> Result<ScriptValue, ScriptError> {
+    match op {
+        BinaryOp::Concat => return Ok(ScriptValue::String(format!("{lhs}{rhs}"))),
+        BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide => {
+            return match (lhs.to_number(), rhs.to_number()) {
+                (Some(lhs), Some(rhs)) => arithmetic(op, lhs, rhs),
+                _ => Err(ScriptError::InvalidOperands(
+                    op.symbol(),
+                    lhs.type_name(),
+                    rhs.type_name(),
+                )),
+            };
+        }
+        _ => {}
+    }
+
+    let ordering = compare(&lhs, &rhs);
+
+    let result = match op {
+        BinaryOp::And | BinaryOp::Or => rhs.to_bool()?,
+        BinaryOp::Equal => ordering == Some(Ordering::Equal),
+        BinaryOp::NotEqual => ordering != Some(Ordering::Equal),
+        _ => {
+            let ordering = ordering.ok_or(ScriptError::InvalidOperands(
+                op.symbol(),
+                lhs.type_name(),
+                rhs.type_name(),
+            ))?;
+
+            match op {
+                BinaryOp::Less => ordering.is_lt(),
+                BinaryOp::LessEqual => ordering.is_le(),
+                BinaryOp::Greater => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        }
+    };
+
+    Ok(ScriptValue::Bool(result))
+}
+
+/// Applies an arithmetic operator to two numbers. Integers stay integers unless
+/// the result overflows or a division isn't exact.
+fn arithmetic(
+    op: BinaryOp,
+    lhs: ScriptValue,
+    rhs: ScriptValue,
+) -> Result<ScriptValue, ScriptError> {
+    if let (ScriptValue::Integer(lhs), ScriptValue::Integer(rhs)) = (&lhs, &rhs) {
+        let result = match op {
+            BinaryOp::Add => lhs.checked_add(*rhs),
+            BinaryOp::Subtract => lhs.checked_sub(*rhs),
+            BinaryOp::Multiply => lhs.checked_mul(*rhs),
+            _ => {
+                if *rhs == 0 {
+                    return Err(ScriptError::DivisionByZero);
+                }
+
+                lhs.checked_rem(*rhs)
+                    .filter(|remainder| *remainder == 0)
+                    .and_then(|_| lhs.checked_div(*rhs))
+            }
+        };
+
+        if let Some(result) = result {
+            return Ok(ScriptValue::Integer(result));
+        }
+    }
+
+    let (lhs, rhs) = (lhs.to_real()?, rhs.to_real()?);
+
+    let result = match op {
+        BinaryOp::Add => lhs + rhs,
+        BinaryOp::Subtract => lhs - rhs,
+        BinaryOp::Multiply => lhs * rhs,
+        _ => {
+            if rhs == 0.0 {
+                return Err(ScriptError::DivisionByZero);
+            }
+
+            lhs / rhs
+        }
+    };
+
+    Ok(ScriptValue::Real(result))
+}
+
+/// Compares two values. Strings are compared to numbers by parsing them,
+/// and returns `None` if that isn't possible.
+fn compare(lhs: &ScriptValue, rhs: &ScriptValue) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (ScriptValue::String(lhs), ScriptValue::String(rhs)) => Some(lhs.cmp(rhs)),
+        (ScriptValue::Integer(lhs), ScriptValue::Integer(rhs)) => Some(lhs.cmp(rhs)),
+        (ScriptValue::Bool(_), _) | (_, ScriptValue::Bool(_)) => {
+            Some(lhs.to_bool().ok()?.cmp(&rhs.to_bool().ok()?))
+        }
+        _ => lhs.to_real().ok()?.partial_cmp(&rhs.to_real().ok()?),
+    }
+}
+
+fn read_entry(blackboard: &mut Blackboard, key: &str) -> Result<ScriptValue, ScriptError> {
+    let entry = blackboard
+        .get_entry(key)
+        .ok_or_else(|| ScriptError::UnknownEntry(key.to_string()))?;
+
+    let entry = lock_entry(&entry);
+
+    // Entries that were created but never set contain `()`
+    if entry.value.is::<()>() {
+        return Err(ScriptError::UnknownEntry(key.to_string()));
+    }
+
+    ScriptValue::from_any(&*entry.value)
+        .ok_or_else(|| ScriptError::UnsupportedType(key.to_string()))
+}
+
+fn write_entry(
+    blackboard: &mut Blackboard,
+    key: &str,
+    op: AssignOp,
+    value: ScriptValue,
+) -> Result<(), ScriptError> {
+    let entry = match blackboard.get_entry(key) {
+        Some(entry) => entry,
+        None => match op {
+            AssignOp::Create => blackboard.create_entry(&key),
+            AssignOp::Assign => return Err(ScriptError::AssignToUnknownEntry(key.to_string())),
+        },
+    };
+
+    let mut entry = lock_entry(&entry);
+
+    if matches!(op, AssignOp::Assign) && entry.value.is::<()>() {
+        return Err(ScriptError::AssignToUnknownEntry(key.to_string()));
+    }
+
+    entry.value = value.into_any_like(key, &*entry.value)?;
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use crate::scripting::{
+    lexer::{tokenize, Token},
+    ScriptError, ScriptValue,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Literal(ScriptValue),
+    Variable(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Assign(String, AssignOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnaryOp {
+    Not,
+    Negate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOp {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Concat,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl BinaryOp {
+    /// Returns the operator for `op` and its precedence. Operators with a higher
+    /// precedence bind more tightly.
+    fn from_operator(op: &str) -> Option<(BinaryOp, u8)> {
+        let op = match op {
+            "||" => (Self::Or, 1),
+            "&&" => (Self::And, 2),
+            "==" => (Self::Equal, 3),
+            "!=" => (Self::NotEqual, 3),
+            "<" => (Self::Less, 4),
+            "<=" => (Self::LessEqual, 4),
+            ">" => (Self::Greater, 4),
+            ">=" => (Self::GreaterEqual, 4),
+            ".." => (Self::Concat, 5),
+            "+" => (Self::Add, 6),
+            "-" => (Self::Subtract, 6),
+            "*" => (Self::Multiply, 7),
+            "/" => (Self::Divide, 7),
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    pub(crate) fn symbol(&self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+            Self::Concat => "..",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AssignOp {
+    /// `:=`, creates the entry if it doesn't exist
+    Create,
+    /// `=`, the entry must already exist
+    Assign,
+}
+
+/// Parses a script into a list of statements, separated by `;`.
+pub(crate) fn parse(source: &str) -> Result<Vec<Expr>, ScriptError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+
+    let mut statements = Vec::new();
+
+    while parser.peek().is_some() {
+        // Allow empty statements, e.g. a trailing `;`
+        if parser.eat_operator(";") {
+            continue;
+        }
+
+        statements.push(parser.statement()?);
+
+        if parser.peek().is_some() {
+            parser.expect_operator(";")?;
+        }
+    }
+
+    if statements.is_empty() {
+        return Err(ScriptError::Empty);
+    }
+
+    Ok(statements)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_operator(&self) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn eat_operator(&mut self, op: &str) -> bool {
+        if self.peek_operator() == Some(op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_operator(&mut self, op: &str) -> Result<(), ScriptError> {
+        if self.eat_operator(op) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("[{op}]")))
+        }
+    }
+
+    /// Creates an error for the current token.
+    fn unexpected(&self, expected: &str) -> ScriptError {
+        match self.tokens.get(self.pos) {
+            Some((token, pos)) => {
+                ScriptError::UnexpectedToken(token.describe(), *pos, expected.to_string())
+            }
+            None => ScriptError::UnexpectedEnd(expected.to_string()),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Expr, ScriptError> {
+        if let Some(Token::Identifier(name)) = self.peek() {
+            let name = name.clone();
+
+            let op = match self.tokens.get(self.pos + 1) {
+                Some((Token::Operator(":="), _)) => Some((AssignOp::Create, None)),
+                Some((Token::Operator("="), _)) => Some((AssignOp::Assign, None)),
+                Some((Token::Operator("+="), _)) => Some((AssignOp::Assign, Some(BinaryOp::Add))),
+                Some((Token::Operator("-="), _)) => {
+                    Some((AssignOp::Assign, Some(BinaryOp::Subtract)))
+                }
+                Some((Token::Operator("*="), _)) => {
+                    Some((AssignOp::Assign, Some(BinaryOp::Multiply)))
+                }
+                Some((Token::Operator("/="), _)) => {
+                    Some((AssignOp::Assign, Some(BinaryOp::Divide)))
+                }
+                _ => None,
+            };
+
+            if let Some((op, compound)) = op {
+                self.pos += 2;
+                let mut value = self.expression(0)?;
+
+                // `a += b` is evaluated as `a = a + b`
+                if let Some(compound) = compound {
+                    value = Expr::Binary(
+                        compound,
+                        Box::new(Expr::Variable(name.clone())),
+                        Box::new(value),
+                    );
+                }
+
+                return Ok(Expr::Assign(name, op, Box::new(value)));
+            }
+        }
+
+        self.expression(0)
+    }
+
+    /// Parses binary operators with a precedence of at least `min_precedence`.
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ScriptError> {
+        let mut lhs = self.unary()?;
+
+        while let Some((op, precedence)) = self.peek_operator().and_then(BinaryOp::from_operator) {
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.pos += 1;
+
+            // All binary operators are left-associative
+            let rhs = self.expression(precedence + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ScriptError> {
+        if self.eat_operator("!") {
+            let expr = self.unary()?;
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(expr)));
+        }
+
+        if self.eat_operator("-") {
+            let expr = self.unary()?;
+            return Ok(Expr::Unary(UnaryOp::Negate, Box::new(expr)));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ScriptError> {
+        let expr = match self.peek() {
+            Some(Token::Integer(value)) => Expr::Literal(ScriptValue::Integer(*value)),
+            Some(Token::Real(value)) => Expr::Literal(ScriptValue::Real(*value)),
+            Some(Token::String(value)) => Expr::Literal(ScriptValue::String(value.clone())),
+            Some(Token::Bool(value)) => Expr::Literal(ScriptValue::Bool(*value)),
+            Some(Token::Identifier(name)) => Expr::Variable(name.clone()),
+            Some(Token::Operator("(")) => {
+                self.pos += 1;
+                let expr = self.expression(0)?;
+                self.expect_operator(")")?;
+                return Ok(expr);
+            }
+            _ => return Err(self.unexpected("a value")),
+        };
+
+        self.pos += 1;
+
+        Ok(expr)
+    }
+}
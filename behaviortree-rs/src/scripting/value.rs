@@ -0,0 +1,209 @@
+use std::{any::Any, fmt};
+
+use crate::{basic_types::FromString, scripting::ScriptError};
+
+/// Value produced when evaluating a script expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptValue {
+    Bool(bool),
+    Integer(i64),
+    Real(f64),
+    String(String),
+}
+
+impl ScriptValue {
+    /// Returns the name of the value's type, used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "bool",
+            Self::Integer(_) => "integer",
+            Self::Real(_) => "real",
+            Self::String(_) => "string",
+        }
+    }
+
+    /// Converts the value to a `bool`. Numbers are `true` if they're not zero,
+    /// and strings are parsed with `FromString`.
+    pub fn to_bool(&self) -> Result<bool, ScriptError> {
+        match self {
+            Self::Bool(value) => Ok(*value),
+            Self::Integer(value) => Ok(*value != 0),
+            Self::Real(value) => Ok(*value != 0.0),
+            Self::String(value) => <bool as FromString>::from_string(value)
+                .map_err(|_| ScriptError::InvalidConversion(self.to_string(), "bool")),
+        }
+    }
+
+    /// Converts the value to a `f64`. Strings are parsed, and booleans are
+    /// converted to `1.0` or `0.0`.
+    pub fn to_real(&self) -> Result<f64, ScriptError> {
+        match self {
+            Self::Bool(value) => Ok(*value as i64 as f64),
+            Self::Integer(value) => Ok(*value as f64),
+            Self::Real(value) => Ok(*value),
+            Self::String(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| ScriptError::InvalidConversion(self.to_string(), "real")),
+        }
+    }
+
+    /// Converts the value to a `i64`. Fails if the value isn't a whole number.
+    pub fn to_integer(&self) -> Result<i64, ScriptError> {
+        let error = || ScriptError::InvalidConversion(self.to_string(), "integer");
+
+        match self {
+            Self::Bool(value) => Ok(*value as i64),
+            Self::Integer(value) => Ok(*value),
+            Self::Real(value) => {
+                if value.fract() == 0.0 && *value >= i64::MIN as f64 && *value <= i64::MAX as f64 {
+                    Ok(*value as i64)
+                } else {
+                    Err(error())
+                }
+            }
+            Self::String(value) => match value.trim().parse::<i64>() {
+                Ok(value) => Ok(value),
+                Err(_) => Self::Real(value.trim().parse().map_err(|_| error())?).to_integer(),
+            },
+        }
+    }
+
+    /// Returns the value as an `Integer` or `Real`, parsing strings. Returns
+    /// `None` for booleans and strings that aren't numbers.
+    pub(crate) fn to_number(&self) -> Option<ScriptValue> {
+        match self {
+            Self::Integer(_) | Self::Real(_) => Some(self.clone()),
+            Self::String(value) => {
+                let value = value.trim();
+
+                value
+                    .parse()
+                    .map(Self::Integer)
+                    .or_else(|_| value.parse().map(Self::Real))
+                    .ok()
+            }
+            Self::Bool(_) => None,
+        }
+    }
+
+    /// Reads a value stored in the `Blackboard`. Returns `None` if the type of
+    /// the stored value isn't supported by scripts.
+    pub(crate) fn from_any(value: &dyn Any) -> Option<ScriptValue> {
+        macro_rules! downcast_integer {
+            ($($t:ty),*) => {
+                $(
+                    if let Some(value) = value.downcast_ref::<$t>() {
+                        return i64::try_from(*value)
+                            .map(Self::Integer)
+                            .ok()
+                            .or(Some(Self::Real(*value as f64)));
+                    }
+                )*
+            };
+        }
+
+        downcast_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+        if let Some(value) = value.downcast_ref::<f64>() {
+            Some(Self::Real(*value))
+        } else if let Some(value) = value.downcast_ref::<f32>() {
+            Some(Self::Real(*value as f64))
+        } else if let Some(value) = value.downcast_ref::<bool>() {
+            Some(Self::Bool(*value))
+        } else if let Some(value) = value.downcast_ref::<String>() {
+            Some(Self::String(value.clone()))
+        } else {
+            value
+                .downcast_ref::<&str>()
+                .map(|value| Self::String(value.to_string()))
+        }
+    }
+
+    /// Converts the value so it can be stored in place of `existing`, keeping
+    /// the type of the existing value. If the existing value is empty, the
+    /// value is stored as a `bool`, `i64`, `f64` or `String`.
+    pub(crate) fn into_any_like(
+        self,
+        key: &str,
+        existing: &dyn Any,
+    ) -> Result<Box<dyn Any + Send>, ScriptError> {
+        macro_rules! convert_integer {
+            ($($t:ty),*) => {
+                $(
+                    if existing.is::<$t>() {
+                        let value = self.to_integer()?;
+                        return <$t>::try_from(value)
+                            .map(|value| Box::new(value) as Box<dyn Any + Send>)
+                            .map_err(|_| {
+                                ScriptError::InvalidConversion(value.to_string(), stringify!($t))
+                            });
+                    }
+                )*
+            };
+        }
+
+        convert_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+        if existing.is::<f64>() {
+            Ok(Box::new(self.to_real()?))
+        } else if existing.is::<f32>() {
+            Ok(Box::new(self.to_real()? as f32))
+        } else if existing.is::<bool>() {
+            Ok(Box::new(self.to_bool()?))
+        } else if existing.is::<String>() || existing.is::<&str>() {
+            Ok(Box::new(self.to_string()))
+        } else if existing.is::<()>() {
+            // Empty entry, so use the type of the value
+            Ok(match self {
+                Self::Bool(value) => Box::new(value),
+                Self::Integer(value) => Box::new(value),
+                Self::Real(value) => Box::new(value),
+                Self::String(value) => Box::new(value),
+            })
+        } else {
+            Err(ScriptError::UnsupportedType(key.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for ScriptValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Real(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<bool> for ScriptValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for ScriptValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for ScriptValue {
+    fn from(value: f64) -> Self {
+        Self::Real(value)
+    }
+}
+
+impl From<String> for ScriptValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for ScriptValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
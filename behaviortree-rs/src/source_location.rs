@@ -0,0 +1,155 @@
+use std::{fmt, path::PathBuf};
+
+/// Position of an XML element that caused a `ParseError`. Errors that occur
+/// while parsing or building a tree are wrapped in `ParseError::Located`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceLocation {
+    /// File containing the XML, or `None` if it was loaded from text
+    pub file: Option<PathBuf>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number, counted in characters
+    pub column: usize,
+    /// ID of the tree being built, if any
+    pub tree_id: Option<String>,
+    /// Path of the node being built, if any
+    pub node_path: Option<String>,
+    /// Contents of the line, used to render a snippet
+    pub source_line: String,
+}
+
+impl SourceLocation {
+    /// Creates the location of the byte at `offset` in `source`. Whitespace at
+    /// `offset` is skipped, so the location points at the next tag.
+    pub(crate) fn at_offset(source: &[u8], offset: usize) -> SourceLocation {
+        let offset = offset.min(source.len());
+        let whitespace = source[offset..]
+            .iter()
+            .take_while(|c| c.is_ascii_whitespace())
+            .count();
+
+        Self::from_offset(source, offset + whitespace)
+    }
+
+    /// Creates the location of the last tag that starts before `offset` in `source`.
+    /// Used when the reader has already moved past the tag that caused the error.
+    pub(crate) fn before_offset(source: &[u8], offset: usize) -> SourceLocation {
+        let offset = offset.min(source.len());
+        let tag_start = source[..offset]
+            .iter()
+            .rposition(|c| *c == b'<')
+            .unwrap_or(offset);
+
+        Self::from_offset(source, tag_start)
+    }
+
+    fn from_offset(source: &[u8], offset: usize) -> SourceLocation {
+        let line_start = source[..offset]
+            .iter()
+            .rposition(|c| *c == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line_end = source[offset..]
+            .iter()
+            .position(|c| *c == b'\n')
+            .map(|i| offset + i)
+            .unwrap_or(source.len());
+
+        let line = source[..line_start].iter().filter(|c| **c == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&source[line_start..offset])
+            .chars()
+            .count()
+            + 1;
+        let source_line = String::from_utf8_lossy(&source[line_start..line_end])
+            .trim_end()
+            .to_string();
+
+        Self {
+            file: None,
+            line,
+            column,
+            tree_id: None,
+            node_path: None,
+            source_line,
+        }
+    }
+
+    /// Returns the name of the tag at this location, if it's a start or empty tag.
+    pub(crate) fn tag_name(&self) -> Option<&str> {
+        let (start, _) = self
+            .source_line
+            .char_indices()
+            .nth(self.column.saturating_sub(1))?;
+        let tag = self.source_line[start..].strip_prefix('<')?;
+
+        let len = tag
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .unwrap_or(tag.len());
+
+        if len == 0 {
+            None
+        } else {
+            Some(&tag[..len])
+        }
+    }
+
+    /// Renders the location along with the line of XML it points to:
+    ///
+    /// ```text
+    ///  --> trees/main.xml:3:17
+    ///   |
+    /// 3 |                 <UnknownNode />
+    ///   |                 ^^^^^^^^^^^^
+    /// ```
+    pub fn snippet(&self) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let underline = match self.tag_name() {
+            // Underline the `<` and the tag name
+            Some(tag) => tag.chars().count() + 1,
+            None => 1,
+        };
+
+        // Keep tabs in the padding so the underline lines up
+        let padding: String = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        format!(
+            "{gutter}--> {}\n{gutter} |\n{line_number} | {}\n{gutter} | {padding}{}",
+            self.position(),
+            self.source_line,
+            "^".repeat(underline),
+        )
+    }
+
+    /// Formats the file, line and column as `file:line:column`.
+    fn position(&self) -> String {
+        let file = match &self.file {
+            Some(file) => file.display().to_string(),
+            None => String::from("<text>"),
+        };
+
+        format!("{file}:{}:{}", self.line, self.column)
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.position())?;
+
+        if let Some(tree_id) = &self.tree_id {
+            write!(f, " in tree [{tree_id}]")?;
+        }
+
+        if let Some(node_path) = &self.node_path {
+            write!(f, " at node [{node_path}]")?;
+        }
+
+        Ok(())
+    }
+}
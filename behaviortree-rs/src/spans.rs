@@ -0,0 +1,142 @@
+//! Support for the `tracing` feature. When enabled, every `execute_tick()`,
+//! halt and tree-level `tick_*()` call runs inside a `tracing::Span` with
+//! the name, registration ID and path of the node, and its resulting status:
+//!
+//! | Span           | Level | Fields                                      |
+//! | -------------- | ----- | ------------------------------------------- |
+//! | `tick_tree`    | INFO  | `method`, `node`, `id`, `path`, `status`    |
+//! | `execute_tick` | DEBUG | `node`, `id`, `path`, `status`              |
+//! | `halt`         | DEBUG | `node`, `id`, `path`, `status`              |
+//!
+//! `status` isn't recorded if the tick returned an error; the `error` field
+//! is recorded instead.
+//!
+//! Without the feature, every function in this module is a no-op.
+
+use std::future::Future;
+
+use crate::nodes::{NodeResult, TreeNodeDefaults};
+
+#[cfg(feature = "tracing")]
+pub use tracing::Span;
+
+/// Placeholder for `tracing::Span` when the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone, Debug)]
+pub struct Span;
+
+#[cfg(feature = "tracing")]
+fn registration_id<N: TreeNodeDefaults + ?Sized>(node: &N) -> &str {
+    node.config()
+        .manifest
+        .as_ref()
+        .map(|m| m.registration_id.as_str())
+        .unwrap_or_default()
+}
+
+/// Returns the span of a tree-level tick of the tree starting at `root`.
+/// `method` is the name of the method, e.g. `tick_while_running`.
+pub fn tree_tick_span<N: TreeNodeDefaults + ?Sized>(root: &N, method: &'static str) -> Span {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info_span!(
+            "tick_tree",
+            method,
+            node = %root.name(),
+            id = registration_id(root),
+            path = %root.path(),
+            status = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (root, method);
+        Span
+    }
+}
+
+/// Returns the span of a call to `execute_tick()` on `node`.
+pub fn tick_span<N: TreeNodeDefaults + ?Sized>(node: &N) -> Span {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!(
+            "execute_tick",
+            node = %node.name(),
+            id = registration_id(node),
+            path = %node.path(),
+            status = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = node;
+        Span
+    }
+}
+
+/// Returns the span of halting `node`.
+pub fn halt_span<N: TreeNodeDefaults + ?Sized>(node: &N) -> Span {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!(
+            "halt",
+            node = %node.name(),
+            id = registration_id(node),
+            path = %node.path(),
+            status = tracing::field::Empty,
+        )
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = node;
+        Span
+    }
+}
+
+/// Runs `future` inside `span`.
+pub fn instrument<F: Future>(span: Span, future: F) -> impl Future<Output = F::Output> {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::Instrument::instrument(future, span)
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = span;
+        future
+    }
+}
+
+/// Runs the tick `future` inside `span`, then records its result in the span.
+pub async fn instrument_tick<F>(span: Span, future: F) -> NodeResult
+where
+    F: Future<Output = NodeResult>,
+{
+    let result = instrument(span.clone(), future).await;
+
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(status) => {
+            span.record("status", tracing::field::display(status));
+        }
+        Err(e) => {
+            span.record("error", tracing::field::display(e));
+        }
+    }
+
+    result
+}
+
+/// Records the status of a node in `span`, e.g. after halting it.
+pub fn record_status<N: TreeNodeDefaults + ?Sized>(span: &Span, node: &N) {
+    #[cfg(feature = "tracing")]
+    span.record("status", tracing::field::display(node.status()));
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = (span, node);
+}
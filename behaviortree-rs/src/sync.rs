@@ -0,0 +1,62 @@
+//! Runs the async parts of the library from sync code, used by `SyncTree`.
+//!
+//! Nodes with the `Sync` runtime, the built-in nodes and the `Blackboard`
+//! never wait, so the futures ticking them complete on their first poll and
+//! don't need an executor. `run_sync()` only blocks the current thread for
+//! futures that really wait, and never from inside a tokio runtime, where
+//! blocking on a future can deadlock or panic.
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll},
+};
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+pub use futures::{executor::block_on, future::BoxFuture};
+
+pub use tokio::sync::Mutex;
+pub use tokio::task::spawn_blocking;
+
+use futures::task::noop_waker_ref;
+
+use crate::nodes::NodeError;
+
+/// Runs `future` to completion from sync code.
+///
+/// If the future completes on its first poll, its output is returned right
+/// away. Otherwise, the current thread blocks until it completes, unless it's
+/// running inside a tokio runtime: the future is dropped and
+/// `NodeError::WouldBlock` is returned instead.
+pub fn run_sync<F: Future>(future: F) -> Result<F::Output, NodeError> {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+        return Ok(output);
+    }
+
+    if Handle::try_current().is_ok() {
+        return Err(NodeError::WouldBlock);
+    }
+
+    Ok(block_on(future))
+}
+
+/// Runs the blocking function `f`, used to tick nodes with the `Sync` runtime.
+///
+/// On a multi-threaded tokio runtime, `f` runs inside
+/// `tokio::task::block_in_place()`, so the other tasks of the worker thread
+/// are moved to another one while it blocks. Elsewhere, including on a
+/// current-thread runtime where `block_in_place()` would panic, `f` runs
+/// inline. Either way, `f` completes before this returns, so a future
+/// calling it completes on its first poll.
+pub fn block_in_place<R>(f: impl FnOnce() -> R) -> R {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
@@ -0,0 +1,171 @@
+//! Ticking a tree periodically, with `AsyncTree::tick_while_running_at()` and
+//! `AsyncTree::run_at_rate()`.
+//!
+//! The ticks are scheduled with tokio timers, so these methods need to run
+//! inside a tokio runtime with the time driver enabled.
+
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+/// Reported to the callback of `TickRate::on_overrun()` when a tick of the
+/// tree takes longer than the period. The next tick starts as soon as the
+/// late one completes, and the following ones are scheduled from there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickOverrun {
+    /// Index of the tick, starting at 0
+    pub tick: u64,
+    /// How long the tick took, not counting the time the tree was paused
+    /// by a `TreeHandle`
+    pub duration: Duration,
+    pub period: Duration,
+}
+
+/// How often a tree is ticked, and how to stop.
+///
+/// # Examples
+///
+/// ```ignore
+/// let cancel = CancelHandle::new();
+/// let rate = TickRate::new(Duration::from_millis(50))
+///     .on_overrun(|overrun| log::warn!("Tick {} took {:?}", overrun.tick, overrun.duration))
+///     .cancel_on(cancel.clone());
+///
+/// // Call cancel.cancel() from anywhere to stop
+/// tree.run_at_rate(rate).await?;
+/// ```
+pub struct TickRate {
+    pub(crate) period: Duration,
+    pub(crate) on_overrun: Option<Box<dyn FnMut(TickOverrun) + Send>>,
+    pub(crate) cancel: Option<CancelHandle>,
+}
+
+impl TickRate {
+    /// Ticks the tree every `period`. Panics if `period` is zero.
+    pub fn new(period: Duration) -> TickRate {
+        assert!(!period.is_zero(), "The tick period can't be zero");
+
+        Self {
+            period,
+            on_overrun: None,
+            cancel: None,
+        }
+    }
+
+    /// Calls `f` every time a tick takes longer than the period.
+    pub fn on_overrun(mut self, f: impl FnMut(TickOverrun) + Send + 'static) -> TickRate {
+        self.on_overrun = Some(Box::new(f));
+        self
+    }
+
+    /// Stops ticking when `handle` is cancelled. The tree is halted first.
+    pub fn cancel_on(mut self, handle: CancelHandle) -> TickRate {
+        self.cancel = Some(handle);
+        self
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Reports the tick if it took longer than the period.
+    pub(crate) fn check_overrun(&mut self, tick: u64, duration: Duration) {
+        if duration <= self.period {
+            return;
+        }
+
+        let overrun = TickOverrun {
+            tick,
+            duration,
+            period: self.period,
+        };
+
+        match &mut self.on_overrun {
+            Some(f) => f(overrun),
+            None => log::debug!(
+                "[behaviortree_rs]: Tick {tick} took {duration:?}, longer than the period of {:?}",
+                self.period
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for TickRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TickRate")
+            .field("period", &self.period)
+            .field("on_overrun", &self.on_overrun.is_some())
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+/// Stops a tree ticked at a `TickRate`. Clones share the same state, so a
+/// clone can be cancelled from another task or thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelHandle {
+    pub fn new() -> CancelHandle {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Waits until the handle is cancelled. Returns immediately if it
+    /// already is.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // Register before checking the flag, so a concurrent cancel()
+            // isn't missed
+            notified.as_mut().enable();
+
+            if self.is_cancelled() {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+/// Runs `future` to completion, unless `cancel` is cancelled first. Then
+/// `future` is dropped and `None` is returned.
+pub(crate) async fn unless_cancelled<F: Future>(
+    cancel: Option<&CancelHandle>,
+    future: F,
+) -> Option<F::Output> {
+    match cancel {
+        Some(cancel) => tokio::select! {
+            biased;
+            _ = cancel.cancelled() => None,
+            output = future => Some(output),
+        },
+        None => Some(future.await),
+    }
+}
@@ -1,25 +1,46 @@
-use std::{collections::HashMap, io::Cursor, string::FromUtf8Error, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    io::Cursor,
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use futures::future::BoxFuture;
 use log::{debug, info};
 use quick_xml::{
-    events::{attributes::Attributes, Event},
+    events::{attributes::Attributes, BytesEnd, BytesStart, Event},
     name::QName,
     Reader,
 };
 use thiserror::Error;
+use tokio::time::MissedTickBehavior;
 
 use crate::{
     basic_types::{
         AttrsToMap, FromString, NodeStatus, NodeType, ParseBoolError, PortChecks, PortDirection,
-        PortsRemapping,
+        PortInfo, PortsList, PortsRemapping, TreeNodeManifest,
     },
     blackboard::{Blackboard, BlackboardString},
+    diagram::{self, DiagramOptions},
+    loggers::{notify_tick_end, StatusChangeLogger, TreeLoggers},
     macros::build_node_ptr,
     nodes::{
-        self, AsyncHalt, NodeConfig,
-        NodeResult, TreeNodeBase, TreeNodePtr,
+        self, halt_node, NodeConfig, NodeError, NodeResult, PostCond, PreCond, SubTreeNode,
+        TreeNodeBase, TreeNodePtr,
     },
+    scripting::{Script, ScriptError},
+    source_location::SourceLocation,
+    spans,
+    sync::run_sync,
+    tick_rate::{unless_cancelled, TickRate},
+    wake_up::WakeUpSignal,
+    xml_writer,
 };
 
 #[derive(Debug, Error)]
@@ -47,71 +68,686 @@ pub enum ParseError {
     MissingAttribute(String),
     #[error("Can't find tree [{0}]")]
     UnknownTree(String),
-    #[error("Node type [] didn't had invalid presence/absence of children.")]
+    #[error("Node type [{0}] has an invalid number of children.")]
     NodeTypeMismatch(String),
     #[error("No main tree was provided, either in the XML or as a function parameter.")]
     NoMainTree,
     #[error("{0}")]
     ParseStringError(#[from] ParseBoolError),
+    #[error("Couldn't read XML file [{0}]: {1}")]
+    /// `(path, error)`
+    FileError(String, std::io::Error),
+    #[error("File [{0}] includes itself, either directly or through other files.")]
+    CircularInclude(String),
+    #[error("Invalid TreeNodesModel entry [{0}]: {1}")]
+    /// `(node_id, reason)`
+    InvalidNodeModel(String, String),
+    #[error("Invalid number of children for node [{0}]: {1}")]
+    /// `(node_id, reason)`
+    InvalidChildCount(String, String),
+    #[error("SubTree [{0}] references itself, either directly or through other SubTrees.")]
+    RecursiveSubTree(String),
+    #[error("Trees can't have more than {} nodes.", u16::MAX - 1)]
+    TooManyNodes,
+    #[error("Invalid script in attribute [{0}]: {1}")]
+    /// `(attribute, error)`
+    InvalidScript(String, ScriptError),
+    /// E.g. `NodeError::WouldBlock`, if building a `SyncTree` had to wait
+    #[error("{0}")]
+    NodeError(#[from] NodeError),
+    #[error("{0}: {1}")]
+    /// `(location, error)`
+    Located(Box<SourceLocation>, Box<ParseError>),
+}
+
+impl ParseError {
+    /// Returns the error without its location.
+    pub fn inner(&self) -> &ParseError {
+        match self {
+            Self::Located(_, error) => error.inner(),
+            error => error,
+        }
+    }
+
+    /// Returns where in the XML the error occurred, if known.
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            Self::Located(location, _) => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Renders the error along with the line of XML that caused it, if known.
+    pub fn snippet(&self) -> Option<String> {
+        self.location()
+            .map(|location| format!("error: {}\n{}", self.inner(), location.snippet()))
+    }
+
+    /// Attaches a location to the error, unless it already has one.
+    fn with_location(self, location: impl FnOnce() -> SourceLocation) -> ParseError {
+        match self {
+            Self::Located(..) => self,
+            error => Self::Located(Box::new(location()), Box::new(error)),
+        }
+    }
+
+    /// Fills in the tree and file of the location, if they aren't known yet.
+    fn with_tree(mut self, tree_id: &str, file: Option<&PathBuf>) -> ParseError {
+        if let Self::Located(location, _) = &mut self {
+            if location.tree_id.is_none() {
+                location.tree_id = Some(tree_id.to_string());
+                location.file = file.cloned();
+            }
+        }
+
+        self
+    }
+}
+
+/// A problem found in a tree by `Factory::validate()`.
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// ID of the tree that contains the node
+    pub tree_id: String,
+    /// Path of the node inside its tree, e.g. `Sequence/Inverter/MyAction`.
+    /// Empty if the problem is with the tree itself.
+    pub node_path: String,
+    pub error: ParseError,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.node_path.is_empty() {
+            write!(f, "[{}]: {}", self.tree_id, self.error)
+        } else {
+            write!(f, "[{}] {}: {}", self.tree_id, self.node_path, self.error)
+        }
+    }
 }
 
 type NodeCreateFnDyn = dyn Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync;
 
+/// The type of a registered node, its manifest if known, and its builder
+type RegisteredNode = (
+    NodeType,
+    Option<Arc<TreeNodeManifest>>,
+    Arc<NodeCreateFnDyn>,
+);
+
 enum TickOption {
-    WhileRunning,
+    /// Sleeps between ticks until woken up, for at most the given duration
+    WhileRunning(Duration),
     ExactlyOnce,
     OnceUnlessWokenUp,
 }
 
+impl TickOption {
+    /// Name of the method of `AsyncTree` using the option
+    fn method(&self) -> &'static str {
+        match self {
+            TickOption::WhileRunning(_) => "tick_while_running",
+            TickOption::ExactlyOnce => "tick_exactly_once",
+            TickOption::OnceUnlessWokenUp => "tick_once",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AsyncTree {
     root: TreeNodePtr,
+    loggers: TreeLoggers,
+    wake_up: WakeUpSignal,
+    handle: TreeHandle,
+    /// Total time spent waiting while paused by the `TreeHandle`, which
+    /// `tick_at_rate()` leaves out of the duration of ticks
+    paused_time: Duration,
 }
 
+/// Maximum time `tick_while_running()` sleeps between ticks
+const DEFAULT_SLEEP: Duration = Duration::from_millis(10);
+
 impl AsyncTree {
-    pub fn new(root: TreeNodePtr) -> AsyncTree {
-        Self { root }
+    pub fn new(mut root: TreeNodePtr) -> AsyncTree {
+        let loggers = TreeLoggers::new();
+        loggers.attach(&mut root);
+
+        // Every node shares the signal of the tree
+        let wake_up = WakeUpSignal::new();
+        visit_node_mut(&mut *root, 0, &mut |node, _| {
+            node.config_mut().wake_up = wake_up.clone()
+        });
+
+        Self {
+            root,
+            loggers,
+            handle: TreeHandle::new(wake_up.clone()),
+            wake_up,
+            paused_time: Duration::ZERO,
+        }
+    }
+
+    /// Returns the root node of the tree.
+    pub fn root(&self) -> &dyn TreeNodeBase {
+        &*self.root
+    }
+
+    /// Returns the node with the given UID. UIDs are unique within a tree.
+    pub fn node_by_uid(&self, uid: u16) -> Option<&dyn TreeNodeBase> {
+        find_node(&*self.root, &|node| node.config().uid == uid)
+    }
+
+    /// Returns the node at `path`, e.g. `main/Sequence/MyAction`. If several
+    /// siblings have the same path, because they don't have a `name` attribute,
+    /// the first one is returned.
+    pub fn node_by_path(&self, path: &str) -> Option<&dyn TreeNodeBase> {
+        find_node(&*self.root, &|node| node.path() == path)
+    }
+
+    /// Calls `f` on every node of the tree in depth-first order, starting at
+    /// the root, with the depth of the node. The root has a depth of 0. The
+    /// children of control nodes, decorators and SubTrees are all visited.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// tree.visit(|node, depth| {
+    ///     println!("{}{} ({:?})", "  ".repeat(depth), node.name(), node.status());
+    /// });
+    /// ```
+    pub fn visit(&self, mut f: impl FnMut(&dyn TreeNodeBase, usize)) {
+        visit_node(&*self.root, 0, &mut f);
+    }
+
+    /// Like `visit()`, but gives mutable access to every node.
+    pub fn visit_mut(&mut self, mut f: impl FnMut(&mut dyn TreeNodeBase, usize)) {
+        visit_node_mut(&mut *self.root, 0, &mut f);
     }
 
     async fn tick_root(&mut self, opt: TickOption) -> NodeResult {
-        let mut status = NodeStatus::Idle;
+        let span = spans::tree_tick_span(&*self.root, opt.method());
 
-        while status == NodeStatus::Idle
-            || (matches!(opt, TickOption::WhileRunning) && matches!(status, NodeStatus::Running))
-        {
-            status = self.root.execute_tick().await?;
+        spans::instrument_tick(span, async {
+            let mut status = NodeStatus::Idle;
 
-            // Not implemented: Check for wake-up conditions and tick again if so
+            while status == NodeStatus::Idle
+                || (matches!(opt, TickOption::WhileRunning(_))
+                    && matches!(status, NodeStatus::Running))
+            {
+                let Some(new_status) = self.execute_root_tick().await? else {
+                    return Ok(NodeStatus::Idle);
+                };
+                status = new_status;
+
+                // Tick again right away if a node emitted the wake-up signal
+                // during the previous tick
+                while !matches!(opt, TickOption::ExactlyOnce)
+                    && status == NodeStatus::Running
+                    && self.wake_up.take()
+                {
+                    let Some(new_status) = self.execute_root_tick().await? else {
+                        return Ok(NodeStatus::Idle);
+                    };
+                    status = new_status;
+                }
 
-            if status.is_completed() {
-                self.root.reset_status();
+                if status.is_completed() {
+                    self.root.reset_status();
+                }
+
+                if let (TickOption::WhileRunning(sleep), NodeStatus::Running) = (&opt, &status) {
+                    self.wake_up.wait_for(*sleep).await;
+                }
             }
+
+            Ok(status)
+        })
+        .await
+    }
+
+    /// Ticks the root once. Waits first while the tree is paused by a
+    /// `TreeHandle`. If a halt was requested, halts the tree instead and
+    /// returns `None`.
+    async fn execute_root_tick(&mut self) -> Result<Option<NodeStatus>, NodeError> {
+        if self.handle.is_paused() {
+            let paused = Instant::now();
+
+            // resume() and request_halt() emit the wake-up signal
+            while self.handle.is_paused() && !self.handle.is_halt_requested() {
+                self.wake_up.wait().await;
+            }
+
+            self.paused_time += paused.elapsed();
         }
 
-        Ok(status)
+        if self.handle.is_halt_requested() {
+            self.halt_tree().await;
+            return Ok(None);
+        }
+
+        let status = self.root.execute_tick().await?;
+        self.handle.set_status(status.clone());
+
+        Ok(Some(status))
     }
 
+    /// Ticks the tree once, even if a node emits the wake-up signal.
     pub async fn tick_exactly_once(&mut self) -> NodeResult {
         self.tick_root(TickOption::ExactlyOnce).await
     }
 
+    /// Ticks the tree once, then again as long as it's running and a node
+    /// emitted the wake-up signal during the previous tick.
     pub async fn tick_once(&mut self) -> NodeResult {
         self.tick_root(TickOption::OnceUnlessWokenUp).await
     }
 
+    /// Ticks the tree until it completes. Between ticks, sleeps until a node
+    /// emits the wake-up signal, or for at most 10ms.
+    ///
+    /// Needs a tokio runtime with the time driver enabled.
     pub async fn tick_while_running(&mut self) -> NodeResult {
-        self.tick_root(TickOption::WhileRunning).await
+        self.tick_while_running_with_sleep(DEFAULT_SLEEP).await
+    }
+
+    /// Same as `tick_while_running()`, but sleeps for at most `max_sleep`
+    /// between ticks. With `Duration::ZERO`, the tree is ticked again
+    /// immediately.
+    pub async fn tick_while_running_with_sleep(&mut self, max_sleep: Duration) -> NodeResult {
+        self.tick_root(TickOption::WhileRunning(max_sleep)).await
+    }
+
+    /// Ticks the tree every `period` until it completes. See
+    /// `tick_while_running_at()`.
+    pub async fn tick_while_running_with_period(&mut self, period: Duration) -> NodeResult {
+        self.tick_while_running_at(TickRate::new(period)).await
+    }
+
+    /// Ticks the tree at `rate` until it completes, and returns the final
+    /// status. Every tick is done with `tick_once()`. If the `CancelHandle`
+    /// of `rate` is cancelled first, the tree is halted and
+    /// `NodeStatus::Idle` is returned. A tick in progress is dropped instead
+    /// of waiting for it to complete.
+    ///
+    /// Needs a tokio runtime with the time driver enabled.
+    pub async fn tick_while_running_at(&mut self, rate: TickRate) -> NodeResult {
+        self.tick_at_rate(rate, true).await
+    }
+
+    /// Ticks the tree at `rate` until the `CancelHandle` of `rate` is
+    /// cancelled, for trees that never complete. If the tree completes, it
+    /// starts again on the next tick. When cancelled, the tree is halted, even
+    /// in the middle of a tick.
+    /// Returns early if a tick returns an error, or if the tree is halted
+    /// with `TreeHandle::request_halt()`.
+    ///
+    /// Needs a tokio runtime with the time driver enabled.
+    pub async fn run_at_rate(&mut self, rate: TickRate) -> Result<(), NodeError> {
+        self.tick_at_rate(rate, false).await.map(|_| ())
+    }
+
+    async fn tick_at_rate(&mut self, mut rate: TickRate, until_completed: bool) -> NodeResult {
+        let mut interval = tokio::time::interval(rate.period);
+        // Don't tick in bursts to catch up after an overrun
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let cancel = rate.cancel.clone();
+
+        let mut tick: u64 = 0;
+        loop {
+            if unless_cancelled(cancel.as_ref(), interval.tick())
+                .await
+                .is_none()
+            {
+                self.halt_tree().await;
+                return Ok(NodeStatus::Idle);
+            }
+
+            let start = Instant::now();
+            let paused_before = self.paused_time;
+            // Cancelling doesn't wait for a slow tick to complete, nor for a
+            // paused tree to be resumed
+            let Some(status) = unless_cancelled(cancel.as_ref(), self.tick_once()).await else {
+                // The loggers never received the end of the dropped tick
+                self.end_open_ticks(&Ok(NodeStatus::Idle));
+                self.halt_tree().await;
+                return Ok(NodeStatus::Idle);
+            };
+            let status = status?;
+            let paused = self.paused_time - paused_before;
+            rate.check_overrun(tick, start.elapsed().saturating_sub(paused));
+
+            // Halted through a TreeHandle
+            if status == NodeStatus::Idle {
+                return Ok(status);
+            }
+
+            if until_completed && status.is_completed() {
+                return Ok(status);
+            }
+
+            tick += 1;
+        }
+    }
+
+    /// Returns the signal that wakes up the tree, shared by every node. See
+    /// the `wake_up` module.
+    pub fn wake_up_signal(&self) -> WakeUpSignal {
+        self.wake_up.clone()
     }
 
     pub async fn root_blackboard(&self) -> Blackboard {
         self.root.config().blackboard.clone()
     }
 
+    /// Notifies the loggers that the ticks left in progress by a dropped tick
+    /// ended with `result`, innermost first.
+    pub(crate) fn end_open_ticks(&self, result: &NodeResult) {
+        for uid in self.loggers.take_open_ticks().into_iter().rev() {
+            if let Some(node) = self.node_by_uid(uid) {
+                notify_tick_end(node, result);
+            }
+        }
+    }
+
+    /// Halts every running node of the tree, running their `halt()` and
+    /// `_onHalted` scripts.
     pub async fn halt_tree(&mut self) {
-        AsyncHalt::halt(&mut *self.root).await;
+        halt_node(&mut *self.root).await;
+        // Like a parent does after halting its child
+        self.root.reset_status();
+
+        self.handle.halted();
+    }
+
+    /// Returns `true` if any node of the tree is running.
+    pub fn is_running(&self) -> bool {
+        find_node(&*self.root, &|node| node.status() == NodeStatus::Running).is_some()
+    }
+
+    /// Shuts the tree down: halts every running node, so their `halt()` and
+    /// `_onHalted` scripts run, then flushes and removes the loggers.
+    ///
+    /// Call this before dropping a tree that may still be running. `Drop`
+    /// can't wait for `halt()` to complete, so dropping a running
+    /// `AsyncTree` only logs a warning, and its nodes are never halted.
+    pub async fn shutdown(mut self) {
+        if self.is_running() {
+            self.halt_tree().await;
+        }
+
+        self.clear_loggers();
+    }
+
+    /// Returns a handle to control the tree from another task or thread,
+    /// e.g. to halt it while `tick_while_running()` is running.
+    pub fn handle(&self) -> TreeHandle {
+        self.handle.clone()
+    }
+
+    /// Attaches a logger, which is notified every time the status of a node
+    /// in the tree changes.
+    pub fn add_logger(&mut self, logger: impl StatusChangeLogger + 'static) {
+        self.loggers.add(logger);
+    }
+
+    /// Flushes and removes every logger attached to the tree.
+    pub fn clear_loggers(&mut self) {
+        self.loggers.clear();
+    }
+
+    /// Serializes the tree into a BehaviorTree.CPP XML document, which can be
+    /// loaded again with `Factory::register_bt_from_text`. SubTrees are written
+    /// as `<SubTree>` references with their own `<BehaviorTree>` definition.
+    pub fn to_xml(&self) -> Result<String, ParseError> {
+        xml_writer::write_tree_to_xml(&*self.root)
+    }
+
+    /// Renders the tree as a Graphviz DOT graph. See
+    /// `diagram::write_tree_to_dot()`.
+    pub fn to_dot(&self, options: &DiagramOptions) -> String {
+        diagram::write_tree_to_dot(&*self.root, options)
+    }
+
+    /// Renders the tree as a Mermaid flowchart. See
+    /// `diagram::write_tree_to_mermaid()`.
+    pub fn to_mermaid(&self, options: &DiagramOptions) -> String {
+        diagram::write_tree_to_mermaid(&*self.root, options)
+    }
+
+    /// Prints the tree to stdout. See the `Display` implementation.
+    pub fn print_tree(&self) {
+        print!("{self}");
+    }
+}
+
+impl Drop for AsyncTree {
+    fn drop(&mut self) {
+        if self.is_running() {
+            log::warn!(
+                "[behaviortree_rs]: Tree [{}] dropped while nodes are running, so they weren't \
+                 halted. Call shutdown().await before dropping the tree.",
+                self.root.name()
+            );
+        }
+    }
+}
+
+/// Renders the tree as an indented tree, one node per line, with the
+/// registration ID, instance name, ports and current status of every node.
+///
+/// ```text
+/// main (SubTree) [IDLE]
+/// └── Sequence [RUNNING]
+///     ├── OpenDoor [SUCCESS]
+///     └── walk (MoveTo) goal="{target}" [RUNNING]
+/// ```
+///
+/// Statuses are colored with `NodeStatus::into_string_color()`, unless
+/// formatted with `{:#}`.
+impl fmt::Display for AsyncTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let color = !f.alternate();
+        write_node_line(f, &*self.root, color)?;
+        write_children(f, &*self.root, "", color)
+    }
+}
+
+fn write_children(
+    f: &mut fmt::Formatter<'_>,
+    node: &dyn TreeNodeBase,
+    prefix: &str,
+    color: bool,
+) -> fmt::Result {
+    let children = node.child_nodes();
+
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        write!(f, "{prefix}{branch}")?;
+        write_node_line(f, &**child, color)?;
+        write_children(f, &**child, &format!("{prefix}{indent}"), color)?;
+    }
+
+    Ok(())
+}
+
+fn write_node_line(
+    f: &mut fmt::Formatter<'_>,
+    node: &dyn TreeNodeBase,
+    color: bool,
+) -> fmt::Result {
+    let config = node.config();
+
+    match config.manifest.as_ref().map(|m| &m.registration_id) {
+        Some(id) if id != node.name() => write!(f, "{} ({id})", node.name())?,
+        _ => write!(f, "{}", node.name())?,
+    }
+
+    // Sort ports so the output is deterministic
+    let mut ports: Vec<(&String, &String)> = config
+        .input_ports
+        .iter()
+        .chain(config.output_ports.iter())
+        .collect();
+    ports.sort();
+
+    for (name, value) in ports {
+        write!(f, " {name}=\"{value}\"")?;
+    }
+
+    let status = if color {
+        node.status().into_string_color()
+    } else {
+        node.status().to_string()
+    };
+
+    writeln!(f, " [{status}]")
+}
+
+/// Returns the first node matching `predicate`, in depth-first order.
+fn find_node<'a>(
+    node: &'a dyn TreeNodeBase,
+    predicate: &dyn Fn(&dyn TreeNodeBase) -> bool,
+) -> Option<&'a dyn TreeNodeBase> {
+    if predicate(node) {
+        return Some(node);
+    }
+
+    node.child_nodes()
+        .iter()
+        .find_map(|child| find_node(&**child, predicate))
+}
+
+/// Controls an `AsyncTree` or `SyncTree` from another task or thread.
+/// Created with `AsyncTree::handle()`; clones control the same tree.
+///
+/// Requests are applied between two ticks of the root: a tick in progress
+/// always completes first. If the tree isn't being ticked, they are applied
+/// by the next `tick_*()` call.
+///
+/// # Examples
+///
+/// ```ignore
+/// let handle = tree.handle();
+///
+/// tokio::spawn(async move {
+///     tokio::signal::ctrl_c().await.unwrap();
+///     handle.request_halt();
+/// });
+///
+/// // Returns NodeStatus::Idle if halted
+/// let status = tree.tick_while_running().await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct TreeHandle {
+    state: Arc<TreeHandleState>,
+    wake_up: WakeUpSignal,
+}
+
+#[derive(Debug)]
+struct TreeHandleState {
+    halt_requested: AtomicBool,
+    paused: AtomicBool,
+    status: std::sync::Mutex<NodeStatus>,
+}
+
+impl TreeHandle {
+    fn new(wake_up: WakeUpSignal) -> TreeHandle {
+        Self {
+            state: Arc::new(TreeHandleState {
+                halt_requested: AtomicBool::new(false),
+                paused: AtomicBool::new(false),
+                status: std::sync::Mutex::new(NodeStatus::Idle),
+            }),
+            wake_up,
+        }
+    }
+
+    /// Halts the tree before its next tick, like `AsyncTree::halt_tree()`, so
+    /// every running node is halted. The `tick_*()` call in progress, if any,
+    /// returns `NodeStatus::Idle`.
+    pub fn request_halt(&self) {
+        self.state.halt_requested.store(true, Ordering::SeqCst);
+        self.wake_up.emit();
+    }
+
+    /// Stops ticking the tree until `resume()` is called. The running nodes
+    /// aren't halted, and the `tick_*()` call in progress waits.
+    pub fn pause(&self) {
+        self.state.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.state.paused.store(false, Ordering::SeqCst);
+        self.wake_up.emit();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if a halt was requested and the tree wasn't halted yet.
+    pub fn is_halt_requested(&self) -> bool {
+        self.state.halt_requested.load(Ordering::SeqCst)
+    }
+
+    /// Returns the status returned by the last tick of the root. It's
+    /// `NodeStatus::Idle` if the tree was never ticked or was halted.
+    pub fn status(&self) -> NodeStatus {
+        self.state
+            .status
+            .lock()
+            .map(|status| status.clone())
+            .unwrap_or(NodeStatus::Idle)
+    }
+
+    fn set_status(&self, status: NodeStatus) {
+        if let Ok(mut current) = self.state.status.lock() {
+            *current = status;
+        }
+    }
+
+    /// Called when the tree is halted, which fulfills any halt request.
+    fn halted(&self) {
+        self.state.halt_requested.store(false, Ordering::SeqCst);
+        self.set_status(NodeStatus::Idle);
     }
 }
 
+fn visit_node(node: &dyn TreeNodeBase, depth: usize, f: &mut dyn FnMut(&dyn TreeNodeBase, usize)) {
+    f(node, depth);
+
+    for child in node.child_nodes() {
+        visit_node(&**child, depth + 1, f);
+    }
+}
+
+fn visit_node_mut(
+    node: &mut dyn TreeNodeBase,
+    depth: usize,
+    f: &mut dyn FnMut(&mut dyn TreeNodeBase, usize),
+) {
+    f(node, depth);
+
+    for child in node.child_nodes_mut() {
+        visit_node_mut(&mut **child, depth + 1, f);
+    }
+}
+
+/// Runs a tree from sync code, blocking the current thread.
+///
+/// A `SyncTree` never nests executors: ticks are polled directly, and only
+/// block the thread if a node has to wait, e.g. an async node doing I/O. See
+/// `sync::run_sync()`. Trees made of built-in nodes and nodes with the `Sync`
+/// runtime never wait, so they can be ticked from anywhere, even inside an
+/// async runtime. There, a node that has to wait makes the tick return
+/// `NodeError::WouldBlock`, since blocking could deadlock the runtime, and
+/// the tree is halted.
 #[derive(Debug)]
 pub struct SyncTree {
     root: AsyncTree,
@@ -125,45 +761,218 @@ impl SyncTree {
     }
 
     pub fn tick_exactly_once(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_exactly_once())
+        self.wait_while_paused();
+        let result = run_sync(self.root.tick_exactly_once());
+        self.finish_tick(result)
     }
 
     pub fn tick_once(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_once())
+        self.wait_while_paused();
+        let result = run_sync(self.root.tick_once());
+        self.finish_tick(result)
+    }
+
+    /// If the tick had to wait inside an async runtime, it was dropped halfway
+    /// through. Ends the ticks left in progress for the loggers and halts the
+    /// tree, so the next tick starts over.
+    fn finish_tick(&mut self, result: Result<NodeResult, NodeError>) -> NodeResult {
+        match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.root.end_open_ticks(&Err(NodeError::WouldBlock));
+                self.halt_tree();
+
+                Err(e)
+            }
+        }
     }
 
     pub fn tick_while_running(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_while_running())
+        self.tick_while_running_with_sleep(DEFAULT_SLEEP)
+    }
+
+    /// See `AsyncTree::tick_while_running_with_sleep()`. The current thread
+    /// sleeps between ticks.
+    pub fn tick_while_running_with_sleep(&mut self, max_sleep: Duration) -> NodeResult {
+        loop {
+            let status = self.tick_once()?;
+            if status != NodeStatus::Running {
+                return Ok(status);
+            }
+
+            self.root.wake_up.wait_blocking(max_sleep);
+        }
+    }
+
+    /// Blocks the current thread while the tree is paused by a `TreeHandle`,
+    /// so the tick doesn't have to wait for `resume()`.
+    fn wait_while_paused(&self) {
+        // resume() and request_halt() emit the wake-up signal
+        while self.root.handle.is_paused() && !self.root.handle.is_halt_requested() {
+            self.root.wake_up.wait_blocking(Duration::MAX);
+        }
+    }
+
+    /// Returns a handle to control the tree from another thread. See
+    /// `AsyncTree::handle()`.
+    pub fn handle(&self) -> TreeHandle {
+        self.root.handle()
+    }
+
+    /// Returns the signal that wakes up the tree. See `AsyncTree::wake_up_signal()`.
+    pub fn wake_up_signal(&self) -> WakeUpSignal {
+        self.root.wake_up_signal()
     }
 
     pub fn root_blackboard(&self) -> Blackboard {
-        futures::executor::block_on(self.root.root_blackboard())
+        self.root.root().config().blackboard.clone()
     }
 
-    pub async fn halt_tree(&mut self) {
-        futures::executor::block_on(self.root.halt_tree());
+    /// Returns the root node of the tree.
+    pub fn root(&self) -> &dyn TreeNodeBase {
+        self.root.root()
+    }
+
+    /// Returns the node with the given UID. See `AsyncTree::node_by_uid()`.
+    pub fn node_by_uid(&self, uid: u16) -> Option<&dyn TreeNodeBase> {
+        self.root.node_by_uid(uid)
+    }
+
+    /// Returns the node at `path`. See `AsyncTree::node_by_path()`.
+    pub fn node_by_path(&self, path: &str) -> Option<&dyn TreeNodeBase> {
+        self.root.node_by_path(path)
+    }
+
+    /// Calls `f` on every node of the tree. See `AsyncTree::visit()`.
+    pub fn visit(&self, f: impl FnMut(&dyn TreeNodeBase, usize)) {
+        self.root.visit(f);
+    }
+
+    /// Calls `f` on every node of the tree. See `AsyncTree::visit_mut()`.
+    pub fn visit_mut(&mut self, f: impl FnMut(&mut dyn TreeNodeBase, usize)) {
+        self.root.visit_mut(f);
+    }
+
+    /// Halts every running node of the tree. See `AsyncTree::halt_tree()`.
+    ///
+    /// Inside an async runtime, a node whose `halt()` has to wait can't be
+    /// halted, and a warning is logged. Use `try_halt_tree()` to get the error.
+    pub fn halt_tree(&mut self) {
+        if let Err(e) = self.try_halt_tree() {
+            log::warn!(
+                "[behaviortree_rs]: Couldn't halt tree [{}]: {e}",
+                self.root.root().name()
+            );
+        }
+    }
+
+    /// Same as `halt_tree()`, but returns `NodeError::WouldBlock` if a node
+    /// had to wait inside an async runtime.
+    pub fn try_halt_tree(&mut self) -> Result<(), NodeError> {
+        run_sync(self.root.halt_tree())
+    }
+
+    /// Returns `true` if any node of the tree is running.
+    pub fn is_running(&self) -> bool {
+        self.root.is_running()
+    }
+
+    /// Shuts the tree down. See `AsyncTree::shutdown()`.
+    ///
+    /// Dropping a `SyncTree` shuts it down too, blocking the current thread
+    /// until every running node is halted.
+    pub fn shutdown(mut self) {
+        self.shutdown_in_place();
+    }
+
+    fn shutdown_in_place(&mut self) {
+        if self.root.is_running() {
+            if let Err(e) = self.try_halt_tree() {
+                log::warn!(
+                    "[behaviortree_rs]: Couldn't halt tree [{}] while shutting it down: {e}",
+                    self.root.root().name()
+                );
+            }
+        }
+
+        self.root.clear_loggers();
+    }
+
+    /// Attaches a logger. See `AsyncTree::add_logger()`.
+    pub fn add_logger(&mut self, logger: impl StatusChangeLogger + 'static) {
+        self.root.add_logger(logger);
+    }
+
+    /// Flushes and removes every logger attached to the tree.
+    pub fn clear_loggers(&mut self) {
+        self.root.clear_loggers();
+    }
+
+    /// Renders the tree as a Graphviz DOT graph. See `AsyncTree::to_dot()`.
+    pub fn to_dot(&self, options: &DiagramOptions) -> String {
+        self.root.to_dot(options)
+    }
+
+    /// Renders the tree as a Mermaid flowchart. See `AsyncTree::to_mermaid()`.
+    pub fn to_mermaid(&self, options: &DiagramOptions) -> String {
+        self.root.to_mermaid(options)
+    }
+
+    /// Prints the tree to stdout. See `AsyncTree::print_tree()`.
+    pub fn print_tree(&self) {
+        self.root.print_tree();
+    }
+
+    /// Serializes the tree into a BehaviorTree.CPP XML document. See
+    /// `AsyncTree::to_xml()`.
+    pub fn to_xml(&self) -> Result<String, ParseError> {
+        self.root.to_xml()
+    }
+}
+
+impl Drop for SyncTree {
+    fn drop(&mut self) {
+        self.shutdown_in_place();
+    }
+}
+
+/// Renders the tree as an indented tree. See the `Display` implementation of
+/// `AsyncTree`.
+impl fmt::Display for SyncTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.root, f)
     }
 }
 
 pub struct Factory {
-    node_map: HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)>,
+    /// Builders of the registered nodes, with their type and manifest, if known
+    node_map: HashMap<String, RegisteredNode>,
+    builtin_ids: HashSet<String>,
     blackboard: Blackboard,
     tree_roots: HashMap<String, Reader<Cursor<Vec<u8>>>>,
+    /// Files that trees were loaded from, to report errors
+    tree_files: HashMap<String, PathBuf>,
+    tree_nodes_model: HashMap<String, TreeNodeManifest>,
     main_tree_id: Option<String>,
-    // TODO: temporary solution, potentially replace later
-    tree_uid: std::sync::Mutex<u32>,
+    /// UID of the next node built, reset for every tree
+    next_uid: std::sync::Mutex<u16>,
 }
 
 impl Factory {
     pub fn new() -> Factory {
         let blackboard = Blackboard::create();
+        let node_map = builtin_nodes();
+        let builtin_ids = node_map.keys().cloned().collect();
 
         Self {
-            node_map: builtin_nodes(),
+            node_map,
+            builtin_ids,
             blackboard,
             tree_roots: HashMap::new(),
+            tree_files: HashMap::new(),
+            tree_nodes_model: HashMap::new(),
             main_tree_id: None,
-            tree_uid: std::sync::Mutex::new(0),
+            next_uid: std::sync::Mutex::new(1),
         }
     }
 
@@ -175,12 +984,97 @@ impl Factory {
         self.blackboard = blackboard;
     }
 
+    /// Registers a node built by `node_fn`. The ports of the node aren't
+    /// known until it's built, so `validate()` doesn't check its attributes and
+    /// `manifest()` returns a manifest without ports. Use
+    /// `register_node_with_manifest()` to describe the ports.
     pub fn register_node<F>(&mut self, name: impl AsRef<str>, node_fn: F, node_type: NodeType)
     where
         F: Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync + 'static,
     {
         self.node_map
-            .insert(name.as_ref().into(), (node_type, Arc::new(node_fn)));
+            .insert(name.as_ref().into(), (node_type, None, Arc::new(node_fn)));
+    }
+
+    /// Same as `register_node()`, with the manifest describing the node, which
+    /// is returned by `manifest()`. The `register_*_node!` macros use this.
+    pub fn register_node_with_manifest<F>(
+        &mut self,
+        name: impl AsRef<str>,
+        node_fn: F,
+        node_type: NodeType,
+        manifest: Arc<TreeNodeManifest>,
+    ) where
+        F: Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync + 'static,
+    {
+        self.node_map.insert(
+            name.as_ref().into(),
+            (node_type, Some(manifest), Arc::new(node_fn)),
+        );
+    }
+
+    /// Returns the manifests parsed from every `<TreeNodesModel>` section
+    /// loaded so far, keyed by node ID.
+    pub fn tree_nodes_model(&self) -> &HashMap<String, TreeNodeManifest> {
+        &self.tree_nodes_model
+    }
+
+    /// Returns the `TreeNodeManifest` of a registered node, captured when it
+    /// was registered. Nodes registered with `register_node()` get a manifest
+    /// without ports.
+    pub fn manifest(&self, node_id: &str) -> Result<Arc<TreeNodeManifest>, ParseError> {
+        let (node_type, manifest, _) = self
+            .node_map
+            .get(node_id)
+            .ok_or_else(|| ParseError::UnknownNode(node_id.to_string()))?;
+
+        Ok(manifest.clone().unwrap_or_else(|| {
+            Arc::new(TreeNodeManifest::new(
+                node_type.clone(),
+                node_id,
+                PortsList::new(),
+                "",
+            ))
+        }))
+    }
+
+    /// Returns the manifest captured when the node was registered, or `None`
+    /// if it was registered with `register_node()`.
+    fn registered_manifest(
+        &self,
+        node_id: &str,
+    ) -> Result<Option<Arc<TreeNodeManifest>>, ParseError> {
+        self.node_map
+            .get(node_id)
+            .map(|(_, manifest, _)| manifest.clone())
+            .ok_or_else(|| ParseError::UnknownNode(node_id.to_string()))
+    }
+
+    /// Generates a `<TreeNodesModel>` document describing every node registered
+    /// by the user (built-in nodes are skipped), plus every SubTree model loaded
+    /// from XML. The output can be loaded by Groot2 as a node palette.
+    pub fn tree_nodes_model_xml(&self) -> Result<String, ParseError> {
+        let mut manifests = Vec::new();
+
+        for node_id in self.node_map.keys() {
+            if !self.builtin_ids.contains(node_id) {
+                manifests.push(self.manifest(node_id)?);
+            }
+        }
+
+        for manifest in self.tree_nodes_model.values() {
+            if matches!(manifest.node_type, NodeType::SubTree) {
+                manifests.push(Arc::new(manifest.clone()));
+            }
+        }
+
+        manifests.sort_by(|a, b| a.registration_id.cmp(&b.registration_id));
+
+        let mut writer = xml_writer::new_writer();
+        xml_writer::write_root_start(&mut writer, None)?;
+        xml_writer::write_tree_nodes_model(&mut writer, manifests.iter().map(|m| &**m))?;
+
+        xml_writer::finish_document(writer)
     }
 
     fn create_node(
@@ -192,11 +1086,13 @@ impl Factory {
         node_fn(config, children)
     }
 
-    fn get_uid(&self) -> u32 {
-        let uid = *self.tree_uid.lock().unwrap();
-        *self.tree_uid.lock().unwrap() += 1;
+    /// Returns a new UID for a node of the tree being built.
+    fn next_uid(&self) -> Result<u16, ParseError> {
+        let mut next_uid = self.next_uid.lock().unwrap_or_else(|e| e.into_inner());
+        let uid = *next_uid;
+        *next_uid = uid.checked_add(1).ok_or(ParseError::TooManyNodes)?;
 
-        uid
+        Ok(uid)
     }
 
     async fn recursively_build_subtree(
@@ -213,13 +1109,50 @@ impl Factory {
             }
         };
 
-        match self
+        let start = reader.buffer_position();
+
+        let result = match self
             .build_child(&mut reader, &blackboard, tree_name, path_prefix)
-            .await?
+            .await
         {
-            Some(child) => Ok(child),
-            None => Err(ParseError::NodeTypeMismatch("SubTree".to_string())),
+            Ok(Some(child)) => Ok(child),
+            Ok(None) => Err(ParseError::NodeTypeMismatch("SubTree".to_string())
+                // Point at the <BehaviorTree> tag
+                .with_location(|| SourceLocation::before_offset(source(&reader), start))),
+            Err(e) => Err(e),
+        };
+
+        result.map_err(|e| e.with_tree(tree_id, self.tree_files.get(tree_id)))
+    }
+
+    /// Wraps the root of a subtree in a `SubTreeNode`, which stores the
+    /// attributes of the `<SubTree>` tag as input ports.
+    fn create_subtree_node(
+        &self,
+        child: TreeNodePtr,
+        name: &str,
+        uid: u16,
+        path: String,
+        blackboard: Blackboard,
+        attributes: &HashMap<String, String>,
+    ) -> Result<TreeNodePtr, ParseError> {
+        let mut config = NodeConfig::new(blackboard);
+        config.uid = uid;
+        config.path = path;
+
+        for (attr, value) in attributes {
+            if attr != "name" && !add_condition(&mut config, attr, value)? {
+                config.add_port(PortDirection::Input, attr.clone(), value.clone());
+            }
         }
+
+        let manifest = TreeNodeManifest::new(NodeType::SubTree, "SubTree", PortsList::new(), "");
+        config.set_manifest(Arc::new(manifest));
+
+        let mut node = SubTreeNode::new(name, config);
+        node.child = Some(child);
+
+        Ok(Box::new(node))
     }
 
     pub fn create_sync_tree_from_text(
@@ -271,17 +1204,7 @@ impl Factory {
         blackboard: &Blackboard,
         main_tree_id: &str,
     ) -> Result<SyncTree, ParseError> {
-        // Clone ptr to Blackboard
-        let blackboard = blackboard.clone();
-
-        let main_tree_id = String::from(main_tree_id);
-
-        let root_node = futures::executor::block_on(self.recursively_build_subtree(
-            &main_tree_id,
-            &String::new(),
-            &String::new(),
-            blackboard,
-        ))?;
+        let root_node = self.build_tree_sync(blackboard, main_tree_id)?;
 
         Ok(SyncTree::new(root_node))
     }
@@ -291,16 +1214,53 @@ impl Factory {
         blackboard: &Blackboard,
         main_tree_id: &str,
     ) -> Result<AsyncTree, ParseError> {
+        let root_node = self.build_tree(blackboard, main_tree_id).await?;
+
+        Ok(AsyncTree::new(root_node))
+    }
+
+    /// Builds the tree `main_tree_id`, wrapped in a `SubTreeNode`. Nodes are
+    /// given UIDs in depth-first order, starting at 1 for the wrapper.
+    async fn build_tree(
+        &mut self,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<TreeNodePtr, ParseError> {
         // Clone ptr to Blackboard
         let blackboard = blackboard.clone();
 
         let main_tree_id = String::from(main_tree_id);
 
+        *self.next_uid.get_mut().unwrap_or_else(|e| e.into_inner()) = 1;
+        let root_uid = self.next_uid()?;
+
         let root_node = self
-            .recursively_build_subtree(&main_tree_id, &String::new(), &String::new(), blackboard)
+            .recursively_build_subtree(
+                &main_tree_id,
+                &main_tree_id,
+                &format!("{main_tree_id}/"),
+                blackboard.clone(),
+            )
             .await?;
 
-        Ok(AsyncTree::new(root_node))
+        self.create_subtree_node(
+            root_node,
+            &main_tree_id,
+            root_uid,
+            main_tree_id.clone(),
+            blackboard,
+            &HashMap::from([(String::from("ID"), main_tree_id.clone())]),
+        )
+    }
+
+    /// Same as `build_tree()`, from sync code. Building a tree doesn't wait,
+    /// but if it has to, see `sync::run_sync()`.
+    fn build_tree_sync(
+        &mut self,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<TreeNodePtr, ParseError> {
+        run_sync(self.build_tree(blackboard, main_tree_id))?
     }
 
     async fn build_leaf_node<'a>(
@@ -310,11 +1270,11 @@ impl Factory {
         config: NodeConfig,
     ) -> Result<TreeNodePtr, ParseError> {
         // Get clone of node from node_map based on tag name
-        let (node_type, node_fn) = self
+        let (node_type, _, node_fn) = self
             .node_map
             .get(node_name)
             .ok_or_else(|| ParseError::UnknownNode(node_name.clone()))?;
-        if !matches!(node_type, NodeType::Action) {
+        if !matches!(node_type, NodeType::Action | NodeType::Condition) {
             return Err(ParseError::NodeTypeMismatch(String::from("Action")));
         }
 
@@ -357,6 +1317,11 @@ impl Factory {
         let mut remap = PortsRemapping::new();
 
         for (port_name, port_value) in attributes.to_map()? {
+            // The instance name and conditions aren't ports
+            if port_name == "name" || add_condition(config, &port_name, &port_value)? {
+                continue;
+            }
+
             remap.insert(port_name, port_value);
         }
 
@@ -397,13 +1362,38 @@ impl Factory {
         Ok(())
     }
 
+    /// Builds the next node in `reader`, and its children. Errors are given the
+    /// location of the node's tag.
     fn build_child<'a>(
         &'a self,
         reader: &'a mut Reader<Cursor<Vec<u8>>>,
         blackboard: &'a Blackboard,
         tree_name: &'a String,
         path_prefix: &'a String,
-    ) -> BoxFuture<Result<Option<TreeNodePtr>, ParseError>> {
+    ) -> BoxFuture<'a, Result<Option<TreeNodePtr>, ParseError>> {
+        Box::pin(async move {
+            let start = reader.buffer_position();
+
+            self.build_node(reader, blackboard, tree_name, path_prefix)
+                .await
+                .map_err(|e| {
+                    e.with_location(|| {
+                        let mut location = SourceLocation::at_offset(source(reader), start);
+                        location.node_path =
+                            location.tag_name().map(|tag| path_prefix.to_owned() + tag);
+                        location
+                    })
+                })
+        })
+    }
+
+    fn build_node<'a>(
+        &'a self,
+        reader: &'a mut Reader<Cursor<Vec<u8>>>,
+        blackboard: &'a Blackboard,
+        tree_name: &'a String,
+        path_prefix: &'a String,
+    ) -> BoxFuture<'a, Result<Option<TreeNodePtr>, ParseError>> {
         Box::pin(async move {
             let mut buf = Vec::new();
 
@@ -417,18 +1407,21 @@ impl Factory {
                 Event::Start(e) => {
                     let node_name = String::from_utf8(e.name().0.into())?;
                     let attributes = e.attributes();
+                    let instance_name = attributes.clone().to_map()?.remove("name");
 
                     debug!("build_child Start: {node_name}");
 
                     let mut config = NodeConfig::new(blackboard.clone());
-                    config.path = path_prefix.to_owned() + &node_name;
+                    config.uid = self.next_uid()?;
+                    config.path =
+                        path_prefix.to_owned() + instance_name.as_ref().unwrap_or(&node_name);
 
-                    let (node_type, node_fn) = self
+                    let (node_type, _, node_fn) = self
                         .node_map
                         .get(&node_name)
                         .ok_or_else(|| ParseError::UnknownNode(node_name.clone()))?;
 
-                    let node = match node_type {
+                    let mut node = match node_type {
                         NodeType::Control => {
                             let children = self
                                 .build_children(
@@ -479,6 +1472,10 @@ impl Factory {
                         x => return Err(ParseError::NodeTypeMismatch(format!("{x:?}"))),
                     };
 
+                    if let Some(instance_name) = instance_name {
+                        node.set_name(instance_name);
+                    }
+
                     Some(node)
                 }
                 // Leaf Node
@@ -486,17 +1483,36 @@ impl Factory {
                     let node_name = String::from_utf8(e.name().0.into())?;
                     debug!("[Leaf node]: {node_name}");
                     let attributes = e.attributes();
+                    let instance_name = attributes.clone().to_map()?.remove("name");
 
                     let mut config = NodeConfig::new(blackboard.clone());
-                    config.path = path_prefix.to_owned() + &node_name;
+                    config.uid = self.next_uid()?;
+                    config.path =
+                        path_prefix.to_owned() + instance_name.as_ref().unwrap_or(&node_name);
 
                     let node = match node_name.as_str() {
                         "SubTree" => {
                             let attributes = attributes.to_map()?;
                             let mut child_blackboard = Blackboard::with_parent(blackboard).await;
 
+                            // Use defaults from the SubTree's model for any port not
+                            // specified in the XML
+                            let mut port_values = attributes.clone();
+                            if let Some(model) = attributes
+                                .get("ID")
+                                .and_then(|id| self.tree_nodes_model.get(id))
+                            {
+                                for (port_name, port_info) in model.ports.iter() {
+                                    if let Some(default) = port_info.default_value() {
+                                        port_values
+                                            .entry(port_name.clone())
+                                            .or_insert_with(|| default.clone());
+                                    }
+                                }
+                            }
+
                             // Process attributes (Ports, special fields, etc)
-                            for (attr, value) in attributes.iter() {
+                            for (attr, value) in port_values.iter() {
                                 // Set autoremapping to true or false
                                 if attr == "_autoremap" {
                                     child_blackboard
@@ -530,51 +1546,217 @@ impl Factory {
                                 subtree_name += "/";
                             }
 
-                            if let Some(name_attr) = attributes.get("name") {
-                                subtree_name += name_attr;
-                            } else {
-                                subtree_name += &format!("{id}::{}", self.get_uid());
-                            }
+                            if let Some(name_attr) = attributes.get("name") {
+                                subtree_name += name_attr;
+                            } else {
+                                subtree_name += &format!("{id}::{}", config.uid);
+                            }
+
+                            let new_prefix = format!("{subtree_name}/");
+
+                            let child = self
+                                .recursively_build_subtree(
+                                    id,
+                                    &subtree_name,
+                                    &new_prefix,
+                                    child_blackboard,
+                                )
+                                .await?;
+
+                            let name = attributes.get("name").unwrap_or(id);
+
+                            self.create_subtree_node(
+                                child,
+                                name,
+                                config.uid,
+                                subtree_name,
+                                blackboard.clone(),
+                                &attributes,
+                            )?
+                        }
+                        _ => {
+                            let mut node =
+                                self.build_leaf_node(&node_name, attributes, config).await?;
+
+                            if let Some(instance_name) = instance_name {
+                                node.set_name(instance_name);
+                            }
+
+                            node
+                        }
+                    };
+
+                    Some(node)
+                }
+                Event::End(_e) => None,
+                e => {
+                    debug!("Other - SHOULDN'T BE HERE");
+                    debug!("{e:?}");
+
+                    return Err(ParseError::InternalError(
+                        "Didn't match one of the expected XML tag types.".to_string(),
+                    ));
+                }
+            };
+
+            Ok(node)
+        })
+    }
+
+    /// Writes the registered tree `tree_id`, and every tree it references with
+    /// `<SubTree>`, into a BehaviorTree.CPP XML document. The tree doesn't need
+    /// to be instantiated, and its nodes don't need to be registered.
+    pub fn write_tree_to_xml(&self, tree_id: &str) -> Result<String, ParseError> {
+        let mut writer = xml_writer::new_writer();
+
+        xml_writer::write_root_start(&mut writer, Some(tree_id))?;
+
+        let mut written = HashSet::new();
+        let mut pending = VecDeque::from([tree_id.to_string()]);
+
+        while let Some(id) = pending.pop_front() {
+            if !written.insert(id.clone()) {
+                continue;
+            }
+
+            let mut reader = match self.tree_roots.get(&id) {
+                Some(root) => root.clone(),
+                None => return Err(ParseError::UnknownTree(id)),
+            };
+
+            let mut start = BytesStart::new("BehaviorTree");
+            start.push_attribute(("ID", id.as_str()));
+            writer.write_event(Event::Start(start))?;
+
+            let mut buf = Vec::new();
+            let mut depth = 0;
+
+            // Copy every event until the end of this BehaviorTree
+            loop {
+                let event = reader.read_event_into(&mut buf)?.into_owned();
+                buf.clear();
+
+                match &event {
+                    Event::Start(e) | Event::Empty(e) => {
+                        if matches!(event, Event::Start(_)) {
+                            depth += 1;
+                        }
+
+                        if e.name().as_ref() == b"SubTree" {
+                            if let Some(subtree_id) = e.attributes().to_map()?.remove("ID") {
+                                pending.push_back(subtree_id);
+                            }
+                        }
+                    }
+                    Event::End(_) if depth == 0 => break,
+                    Event::End(_) => depth -= 1,
+                    Event::Eof => return Err(ParseError::UnexpectedEof),
+                    _ => {}
+                }
+
+                writer.write_event(event)?;
+            }
+
+            writer.write_event(Event::End(BytesEnd::new("BehaviorTree")))?;
+        }
+
+        xml_writer::finish_document(writer)
+    }
+
+    /// Renders the registered tree `tree_id` as a Graphviz DOT graph. The
+    /// tree is instantiated with an empty blackboard, so its nodes need to be
+    /// registered. See `diagram::write_tree_to_dot()`.
+    pub fn write_tree_to_dot(
+        &mut self,
+        tree_id: &str,
+        options: &DiagramOptions,
+    ) -> Result<String, ParseError> {
+        let root = self.build_tree_sync(&Blackboard::create(), tree_id)?;
+
+        Ok(diagram::write_tree_to_dot(&*root, options))
+    }
+
+    /// Renders the registered tree `tree_id` as a Mermaid flowchart. See
+    /// `Factory::write_tree_to_dot()`.
+    pub fn write_tree_to_mermaid(
+        &mut self,
+        tree_id: &str,
+        options: &DiagramOptions,
+    ) -> Result<String, ParseError> {
+        let root = self.build_tree_sync(&Blackboard::create(), tree_id)?;
+
+        Ok(diagram::write_tree_to_mermaid(&*root, options))
+    }
+
+    /// Registers every BehaviorTree defined in `xml`. Relative paths in
+    /// `<include path="..."/>` tags are resolved from the current working directory.
+    pub fn register_bt_from_text(&mut self, xml: String) -> Result<(), ParseError> {
+        self.register_bt(xml, None, false, &mut Vec::new())
+    }
+
+    /// Registers every BehaviorTree defined in the XML file at `path`. Relative paths
+    /// in `<include path="..."/>` tags are resolved from the directory of the file
+    /// that contains the tag.
+    pub fn register_bt_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), ParseError> {
+        self.register_bt_file(path.as_ref(), false, &mut Vec::new())
+    }
+
+    fn register_bt_file(
+        &mut self,
+        path: &Path,
+        is_included: bool,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        let file_error = |e| ParseError::FileError(path.display().to_string(), e);
 
-                            let new_prefix = format!("{subtree_name}/");
+        let path = path.canonicalize().map_err(file_error)?;
 
-                            self.recursively_build_subtree(
-                                id,
-                                &subtree_name,
-                                &new_prefix,
-                                child_blackboard,
-                            )
-                            .await?
-                        }
-                        _ => self.build_leaf_node(&node_name, attributes, config).await?,
-                    };
+        if include_stack.contains(&path) {
+            return Err(ParseError::CircularInclude(path.display().to_string()));
+        }
 
-                    Some(node)
-                }
-                Event::End(_e) => None,
-                e => {
-                    debug!("Other - SHOULDN'T BE HERE");
-                    debug!("{e:?}");
+        let xml = std::fs::read_to_string(&path).map_err(file_error)?;
 
-                    return Err(ParseError::InternalError(
-                        "Didn't match one of the expected XML tag types.".to_string(),
-                    ));
-                }
-            };
+        include_stack.push(path.clone());
+        let result = self.register_bt(xml, Some(&path), is_included, include_stack);
+        include_stack.pop();
 
-            Ok(node)
-        })
+        result
     }
 
-    pub fn register_bt_from_text(&mut self, xml: String) -> Result<(), ParseError> {
+    /// Registers the trees in `xml`, loaded from `file` if it's `Some`. `include_stack`
+    /// holds every file currently being loaded, to detect circular includes.
+    fn register_bt(
+        &mut self,
+        xml: String,
+        file: Option<&Path>,
+        is_included: bool,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
         let mut reader = Reader::from_reader(Cursor::new(xml.as_bytes().to_vec()));
         reader.trim_text(true);
 
-        let mut buf = Vec::new();
-
-        // TODO: Check includes
+        self.register_bt_events(&mut reader, file, is_included, include_stack)
+            .map_err(|e| {
+                e.with_location(|| {
+                    // The reader is past the tag that caused the error
+                    let mut location =
+                        SourceLocation::before_offset(source(&reader), reader.buffer_position());
+                    location.file = file.map(Path::to_path_buf);
+                    location
+                })
+            })
+    }
 
-        // TODO: Parse for correctness
+    fn register_bt_events(
+        &mut self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+        file: Option<&Path>,
+        is_included: bool,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        let base_dir = file.and_then(Path::parent);
+        let mut buf = Vec::new();
 
         loop {
             // Try to match root tag
@@ -590,9 +1772,12 @@ impl Factory {
                         continue;
                     }
 
+                    // Only the top-level file can choose the main tree
                     if let Some(tree_id) = attributes.get("main_tree_to_execute") {
-                        info!("Found main tree ID: {tree_id}");
-                        self.main_tree_id = Some(tree_id.clone());
+                        if !is_included {
+                            info!("Found main tree ID: {tree_id}");
+                            self.main_tree_id = Some(tree_id.clone());
+                        }
                     }
 
                     buf.clear();
@@ -617,10 +1802,11 @@ impl Factory {
                     let end_name = end.name().as_ref().to_vec().clone();
                     let end_name = QName(end_name.as_slice());
 
-                    // TODO: Maybe do something with TreeNodesModel?
-                    // For now, just ignore it
                     if name.as_str() == "TreeNodesModel" {
+                        self.parse_tree_nodes_model(reader)?;
+                    } else if name.as_str() == "include" {
                         reader.read_to_end_into(end_name, &mut buf)?;
+                        self.register_include(&attributes, base_dir, include_stack)?;
                     } else {
                         // Add error for missing BT
                         if name.as_str() != "BehaviorTree" {
@@ -630,6 +1816,13 @@ impl Factory {
                         // Save position of Reader for each BT
                         if let Some(id) = attributes.get("ID") {
                             self.tree_roots.insert(id.clone(), reader.clone());
+
+                            match file {
+                                Some(file) => {
+                                    self.tree_files.insert(id.clone(), file.to_path_buf())
+                                }
+                                None => self.tree_files.remove(id),
+                            };
                         } else {
                             return Err(ParseError::MissingAttribute("Found BehaviorTree definition without ID. Cannot continue parsing.".to_string()));
                         }
@@ -637,6 +1830,16 @@ impl Factory {
                         reader.read_to_end_into(end_name, &mut buf)?;
                     }
                 }
+                Event::Empty(e) => {
+                    let name = String::from_utf8(e.name().0.into())?;
+                    let attributes = e.attributes().to_map()?;
+
+                    if name.as_str() != "include" {
+                        return Err(ParseError::ExpectedRoot(name));
+                    }
+
+                    self.register_include(&attributes, base_dir, include_stack)?;
+                }
                 Event::End(e) => {
                     let name = String::from_utf8(e.name().0.into())?;
                     if name != "root" {
@@ -659,15 +1862,547 @@ impl Factory {
     }
 }
 
+impl Factory {
+    /// Loads the file referenced by an `<include path="..."/>` tag.
+    fn register_include(
+        &mut self,
+        attributes: &HashMap<String, String>,
+        base_dir: Option<&Path>,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        let path = match attributes.get("path") {
+            Some(path) => Path::new(path),
+            None => {
+                return Err(ParseError::MissingAttribute(
+                    "Found <include> without path. Cannot continue parsing.".to_string(),
+                ))
+            }
+        };
+
+        // `join()` keeps `path` as-is if it's absolute
+        let path = match base_dir {
+            Some(base_dir) => base_dir.join(path),
+            None => path.to_path_buf(),
+        };
+
+        debug!("Including file: {}", path.display());
+
+        self.register_bt_file(&path, true, include_stack)
+    }
+
+    /// Parses the contents of a `<TreeNodesModel>` tag, stopping after its end tag.
+    /// Each model is checked against the registered node with the same ID, if any.
+    fn parse_tree_nodes_model(
+        &mut self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+    ) -> Result<(), ParseError> {
+        let mut buf = Vec::new();
+
+        loop {
+            let (tag, attributes, has_ports) = match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => (
+                    String::from_utf8(e.name().0.into())?,
+                    e.attributes().to_map()?,
+                    true,
+                ),
+                Event::Empty(e) => (
+                    String::from_utf8(e.name().0.into())?,
+                    e.attributes().to_map()?,
+                    false,
+                ),
+                // End of </TreeNodesModel>
+                Event::End(_) => break,
+                Event::Eof => return Err(ParseError::UnexpectedEof),
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+            buf.clear();
+
+            let node_id = match attributes.get("ID") {
+                Some(id) => id.clone(),
+                None => {
+                    // Nothing can refer to a model without ID
+                    log::warn!("[behaviortree_rs]: Skipping <{tag}> in TreeNodesModel without ID");
+                    if has_ports {
+                        reader.read_to_end_into(QName(tag.as_bytes()), &mut buf)?;
+                        buf.clear();
+                    }
+                    continue;
+                }
+            };
+
+            let node_type = <NodeType as FromString>::from_string(&tag).map_err(|_| {
+                ParseError::InvalidNodeModel(node_id.clone(), format!("unknown node type <{tag}>"))
+            })?;
+
+            let ports = match has_ports {
+                true => parse_model_ports(reader, &node_id)?,
+                false => PortsList::new(),
+            };
+
+            let description = attributes.get("description").cloned().unwrap_or_default();
+            let manifest = TreeNodeManifest::new(node_type, &node_id, ports, description);
+
+            if self.node_map.contains_key(&node_id) {
+                self.validate_node_model(&manifest)?;
+            }
+
+            self.tree_nodes_model.insert(node_id, manifest);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a model from `<TreeNodesModel>` matches the registered node.
+    fn validate_node_model(&self, model: &TreeNodeManifest) -> Result<(), ParseError> {
+        // The ports of nodes registered without a manifest are unknown
+        let Some(manifest) = self.registered_manifest(&model.registration_id)? else {
+            return Ok(());
+        };
+        let id = &model.registration_id;
+
+        // Conditions are registered as Actions
+        let type_matches = model.node_type == manifest.node_type
+            || matches!(
+                (&model.node_type, &manifest.node_type),
+                (NodeType::Condition, NodeType::Action)
+            );
+
+        if !type_matches {
+            return Err(ParseError::InvalidNodeModel(
+                id.clone(),
+                format!(
+                    "declared as {}, but registered as {}",
+                    model.node_type, manifest.node_type
+                ),
+            ));
+        }
+
+        for (port_name, port_info) in model.ports.iter() {
+            match manifest.ports.get(port_name) {
+                Some(registered) => {
+                    if port_info.direction() != registered.direction() {
+                        return Err(ParseError::InvalidNodeModel(
+                            id.clone(),
+                            format!(
+                                "port [{port_name}] declared as {}, but registered as {}",
+                                port_info.direction(),
+                                registered.direction()
+                            ),
+                        ));
+                    }
+                }
+                None => {
+                    return Err(ParseError::InvalidNodeModel(
+                        id.clone(),
+                        format!("port [{port_name}] isn't provided by the registered node"),
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// State shared while walking the trees in `Factory::validate()`.
+#[derive(Default)]
+struct ValidationState {
+    diagnostics: Vec<Diagnostic>,
+    /// Trees that have already been checked
+    visited: HashSet<String>,
+    /// Trees currently being checked, to detect recursive SubTrees
+    stack: Vec<String>,
+}
+
+impl ValidationState {
+    fn report(&mut self, tree_id: &str, node_path: &str, error: ParseError) {
+        self.diagnostics.push(Diagnostic {
+            tree_id: tree_id.to_string(),
+            node_path: node_path.to_string(),
+            error,
+        });
+    }
+}
+
+impl Factory {
+    /// Checks the registered tree `tree_id`, and every tree it references with
+    /// `<SubTree>`, without instantiating it. Unlike instantiation, which stops at
+    /// the first error, every problem found is returned. An empty `Vec` means
+    /// the tree can be instantiated.
+    ///
+    /// The following problems are reported:
+    /// - Nodes that aren't registered
+    /// - SubTrees without an `ID`, or referencing a tree that isn't registered
+    /// - SubTrees that reference themselves, either directly or through other SubTrees
+    /// - Decorators that don't have exactly one child
+    /// - Leaf nodes and SubTrees with children
+    /// - Attributes that aren't in the node's `provided_ports()`
+    pub fn validate(&self, tree_id: &str) -> Vec<Diagnostic> {
+        let mut state = ValidationState::default();
+
+        self.validate_tree(tree_id, tree_id, "", &mut state);
+
+        state.diagnostics
+    }
+
+    /// Checks the tree `tree_id`, referenced by the node at `node_path` in `parent_id`.
+    fn validate_tree(
+        &self,
+        tree_id: &str,
+        parent_id: &str,
+        node_path: &str,
+        state: &mut ValidationState,
+    ) {
+        if state.stack.iter().any(|id| id == tree_id) {
+            state.report(
+                parent_id,
+                node_path,
+                ParseError::RecursiveSubTree(tree_id.to_string()),
+            );
+            return;
+        }
+
+        if state.visited.contains(tree_id) {
+            return;
+        }
+
+        let mut reader = match self.tree_roots.get(tree_id) {
+            Some(root) => root.clone(),
+            None => {
+                state.report(
+                    parent_id,
+                    node_path,
+                    ParseError::UnknownTree(tree_id.to_string()),
+                );
+                return;
+            }
+        };
+
+        state.visited.insert(tree_id.to_string());
+        state.stack.push(tree_id.to_string());
+
+        let mut num_roots = 0;
+        loop {
+            match self.validate_child(&mut reader, tree_id, "", state) {
+                Ok(true) => num_roots += 1,
+                Ok(false) => break,
+                // The XML can't be read any further
+                Err(e) => {
+                    state.report(tree_id, "", e);
+                    break;
+                }
+            }
+        }
+
+        if num_roots != 1 {
+            state.report(
+                tree_id,
+                "",
+                ParseError::InvalidChildCount(
+                    "BehaviorTree".to_string(),
+                    format!("expected exactly 1 root node, found {num_roots}"),
+                ),
+            );
+        }
+
+        state.stack.pop();
+    }
+
+    /// Checks the next node in `reader` and its children. Returns `false` if
+    /// the end tag of the parent was reached instead.
+    fn validate_child(
+        &self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+        tree_id: &str,
+        path_prefix: &str,
+        state: &mut ValidationState,
+    ) -> Result<bool, ParseError> {
+        let mut buf = Vec::new();
+
+        let (node_name, attributes, has_children) = match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => (
+                String::from_utf8(e.name().0.into())?,
+                e.attributes().to_map()?,
+                true,
+            ),
+            Event::Empty(e) => (
+                String::from_utf8(e.name().0.into())?,
+                e.attributes().to_map()?,
+                false,
+            ),
+            Event::End(_) => return Ok(false),
+            Event::Eof => return Err(ParseError::UnexpectedEof),
+            _ => {
+                return Err(ParseError::InternalError(
+                    "Didn't match one of the expected XML tag types.".to_string(),
+                ))
+            }
+        };
+
+        let path = path_prefix.to_owned() + &node_name;
+
+        let mut num_children = 0;
+        if has_children {
+            while self.validate_child(reader, tree_id, &(path.clone() + "/"), state)? {
+                num_children += 1;
+            }
+        }
+
+        self.validate_node(tree_id, &path, &node_name, &attributes, num_children, state);
+
+        Ok(true)
+    }
+
+    fn validate_node(
+        &self,
+        tree_id: &str,
+        path: &str,
+        node_name: &str,
+        attributes: &HashMap<String, String>,
+        num_children: usize,
+        state: &mut ValidationState,
+    ) {
+        // Sort attributes so the diagnostics are deterministic
+        let mut attr_names: Vec<&String> = attributes.keys().collect();
+        attr_names.sort();
+
+        // Any node can have pre- and post-conditions
+        for attr in attr_names.iter().filter(|attr| is_condition(attr)) {
+            if let Err(e) = Script::parse(&attributes[*attr]) {
+                state.report(
+                    tree_id,
+                    path,
+                    ParseError::InvalidScript(attr.to_string(), e),
+                );
+            }
+        }
+
+        if node_name == "SubTree" {
+            if num_children > 0 {
+                state.report(
+                    tree_id,
+                    path,
+                    ParseError::InvalidChildCount(
+                        node_name.to_string(),
+                        format!("SubTrees can't have children, found {num_children}"),
+                    ),
+                );
+            }
+
+            match attributes.get("ID") {
+                Some(id) => self.validate_tree(id, tree_id, path, state),
+                None => state.report(
+                    tree_id,
+                    path,
+                    ParseError::MissingAttribute("Found SubTree without ID.".to_string()),
+                ),
+            }
+
+            return;
+        }
+
+        let node_type = match self.node_map.get(node_name) {
+            Some((node_type, ..)) => node_type,
+            None => {
+                state.report(
+                    tree_id,
+                    path,
+                    ParseError::UnknownNode(node_name.to_string()),
+                );
+                return;
+            }
+        };
+
+        let reason = match node_type {
+            NodeType::Decorator if num_children != 1 => Some(format!(
+                "Decorators must have exactly 1 child, found {num_children}"
+            )),
+            NodeType::Action | NodeType::Condition if num_children > 0 => Some(format!(
+                "{node_type} nodes can't have children, found {num_children}"
+            )),
+            _ => None,
+        };
+
+        if let Some(reason) = reason {
+            state.report(
+                tree_id,
+                path,
+                ParseError::InvalidChildCount(node_name.to_string(), reason),
+            );
+        }
+
+        let manifest = match self.registered_manifest(node_name) {
+            Ok(Some(manifest)) => manifest,
+            // The ports of nodes registered without a manifest are unknown
+            Ok(None) => return,
+            Err(e) => {
+                state.report(tree_id, path, e);
+                return;
+            }
+        };
+
+        for port_name in attr_names {
+            // The instance name and conditions aren't ports
+            if port_name != "name"
+                && !is_condition(port_name)
+                && !manifest.ports.contains_key(port_name)
+            {
+                state.report(
+                    tree_id,
+                    path,
+                    ParseError::InvalidPort(
+                        port_name.clone(),
+                        node_name.to_string(),
+                        manifest.ports.keys().cloned().collect(),
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the XML that `reader` is reading from.
+fn source(reader: &Reader<Cursor<Vec<u8>>>) -> &[u8] {
+    reader.get_ref().get_ref()
+}
+
+/// Parses the `<input_port>`, `<output_port>` and `<inout_port>` tags of a
+/// single TreeNodesModel entry, stopping after the entry's end tag.
+fn parse_model_ports(
+    reader: &mut Reader<Cursor<Vec<u8>>>,
+    node_id: &str,
+) -> Result<PortsList, ParseError> {
+    let mut ports = PortsList::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let (tag, attributes, has_description) = match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => (
+                String::from_utf8(e.name().0.into())?,
+                e.attributes().to_map()?,
+                true,
+            ),
+            Event::Empty(e) => (
+                String::from_utf8(e.name().0.into())?,
+                e.attributes().to_map()?,
+                false,
+            ),
+            // End of the node entry
+            Event::End(_) => break,
+            Event::Eof => return Err(ParseError::UnexpectedEof),
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+        buf.clear();
+
+        let direction = match tag.as_str() {
+            "input_port" => PortDirection::Input,
+            "output_port" => PortDirection::Output,
+            "inout_port" => PortDirection::InOut,
+            _ => {
+                return Err(ParseError::InvalidNodeModel(
+                    node_id.to_string(),
+                    format!("unexpected tag <{tag}>"),
+                ))
+            }
+        };
+
+        let port_name = match attributes.get("name") {
+            Some(name) => name.clone(),
+            None => {
+                return Err(ParseError::InvalidNodeModel(
+                    node_id.to_string(),
+                    format!("<{tag}> is missing the name attribute"),
+                ))
+            }
+        };
+
+        let mut port_info = PortInfo::new(direction);
+
+        if let Some(default) = attributes.get("default") {
+            port_info.set_default(default.clone());
+        }
+
+        if has_description {
+            // Description is the text inside the port tag
+            let mut description = String::new();
+            loop {
+                match reader.read_event_into(&mut buf)? {
+                    Event::Text(text) => description += &text.unescape()?,
+                    Event::End(_) => break,
+                    Event::Eof => return Err(ParseError::UnexpectedEof),
+                    _ => {}
+                }
+                buf.clear();
+            }
+            buf.clear();
+
+            port_info.set_description(description);
+        }
+
+        ports.insert(port_name, port_info);
+    }
+
+    Ok(ports)
+}
+
 impl Default for Factory {
     fn default() -> Self {
         Self::new()
     }
 }
 
-fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
+/// Builds the manifest of a built-in node from an instance that's never ticked.
+macro_rules! builtin_manifest {
+    ($id:expr, $t:ty) => {{
+        use crate::nodes::{GetNodeType, NodePorts};
+
+        let node = <$t>::new($id, NodeConfig::new(Blackboard::create()));
+        Arc::new(TreeNodeManifest::new(
+            node.node_type(),
+            $id,
+            node.provided_ports(),
+            "",
+        ))
+    }};
+}
+
+fn builtin_nodes() -> HashMap<String, RegisteredNode> {
     let mut node_map = HashMap::new();
 
+    // Action nodes
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "Script", nodes::action::ScriptNode)
+        },
+    ) as Arc<NodeCreateFnDyn>;
+    let manifest = builtin_manifest!("Script", nodes::action::ScriptNode);
+    node_map.insert(
+        String::from("Script"),
+        (NodeType::Action, Some(manifest), node),
+    );
+
+    // Condition nodes
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(
+                config,
+                "ScriptCondition",
+                nodes::action::ScriptConditionNode
+            )
+        },
+    );
+    let manifest = builtin_manifest!("ScriptCondition", nodes::action::ScriptConditionNode);
+    node_map.insert(
+        String::from("ScriptCondition"),
+        (NodeType::Condition, Some(manifest), node),
+    );
+
     // Control nodes
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -677,7 +2412,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     ) as Arc<NodeCreateFnDyn>;
-    node_map.insert(String::from("Sequence"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("Sequence", nodes::control::SequenceNode);
+    node_map.insert(
+        String::from("Sequence"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -691,7 +2430,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("ReactiveSequence"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("ReactiveSequence", nodes::control::ReactiveSequenceNode);
+    node_map.insert(
+        String::from("ReactiveSequence"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -705,7 +2448,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("SequenceStar"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("SequenceStar", nodes::control::SequenceWithMemoryNode);
+    node_map.insert(
+        String::from("SequenceStar"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -715,7 +2462,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("Parallel"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("Parallel", nodes::control::ParallelNode);
+    node_map.insert(
+        String::from("Parallel"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -725,7 +2476,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("ParallelAll"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("ParallelAll", nodes::control::ParallelAllNode);
+    node_map.insert(
+        String::from("ParallelAll"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -735,7 +2490,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("Fallback"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("Fallback", nodes::control::FallbackNode);
+    node_map.insert(
+        String::from("Fallback"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -749,7 +2508,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("ReactiveFallback"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("ReactiveFallback", nodes::control::ReactiveFallbackNode);
+    node_map.insert(
+        String::from("ReactiveFallback"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -759,7 +2522,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("IfThenElse"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("IfThenElse", nodes::control::IfThenElseNode);
+    node_map.insert(
+        String::from("IfThenElse"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -769,7 +2536,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("WhileDoElse"), (NodeType::Control, node));
+    let manifest = builtin_manifest!("WhileDoElse", nodes::control::WhileDoElseNode);
+    node_map.insert(
+        String::from("WhileDoElse"),
+        (NodeType::Control, Some(manifest), node),
+    );
 
     // Decorator nodes
     let node = Arc::new(
@@ -781,7 +2552,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("ForceFailure"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("ForceFailure", nodes::decorator::ForceFailureNode);
+    node_map.insert(
+        String::from("ForceFailure"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -792,7 +2567,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("ForceSuccess"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("ForceSuccess", nodes::decorator::ForceSuccessNode);
+    node_map.insert(
+        String::from("ForceSuccess"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -802,7 +2581,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("Inverter"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("Inverter", nodes::decorator::InverterNode);
+    node_map.insert(
+        String::from("Inverter"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -816,9 +2599,13 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
+    let manifest = builtin_manifest!(
+        "KeepRunningUntilFailure",
+        nodes::decorator::KeepRunningUntilFailureNode
+    );
     node_map.insert(
         String::from("KeepRunningUntilFailure"),
-        (NodeType::Decorator, node),
+        (NodeType::Decorator, Some(manifest), node),
     );
 
     let node = Arc::new(
@@ -829,7 +2616,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("Repeat"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("Repeat", nodes::decorator::RepeatNode);
+    node_map.insert(
+        String::from("Repeat"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -839,7 +2630,11 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("Retry"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("Retry", nodes::decorator::RetryNode);
+    node_map.insert(
+        String::from("Retry"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -849,7 +2644,33 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
             node
         },
     );
-    node_map.insert(String::from("RunOnce"), (NodeType::Decorator, node));
+    let manifest = builtin_manifest!("RunOnce", nodes::decorator::RunOnceNode);
+    node_map.insert(
+        String::from("RunOnce"),
+        (NodeType::Decorator, Some(manifest), node),
+    );
 
     node_map
 }
+
+/// Returns `true` if `attr` is the name of a pre- or post-condition attribute.
+fn is_condition(attr: &str) -> bool {
+    PreCond::from_attribute_name(attr).is_some() || PostCond::from_attribute_name(attr).is_some()
+}
+
+/// Parses `value` as the script of the pre- or post-condition in attribute
+/// `attr`, and adds it to `config`. Returns `false` if `attr` isn't a condition.
+fn add_condition(config: &mut NodeConfig, attr: &str, value: &str) -> Result<bool, ParseError> {
+    let script =
+        || Script::parse(value).map_err(|e| ParseError::InvalidScript(attr.to_string(), e));
+
+    if let Some(condition) = PreCond::from_attribute_name(attr) {
+        config.set_pre_condition(condition, script()?);
+    } else if let Some(condition) = PostCond::from_attribute_name(attr) {
+        config.set_post_condition(condition, script()?);
+    } else {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,136 @@
+//! Lets nodes ask the tree to be ticked again right away.
+//!
+//! Every node of a tree shares the same `WakeUpSignal`, which can be cloned
+//! from `NodeConfig::wake_up_signal()`. An async node that makes progress in
+//! the background, e.g. when a request completes, emits the signal so the
+//! tree doesn't wait for its next tick to notice:
+//!
+//! - `tick_once()` ticks the tree again as long as it's running and the
+//!   signal was emitted during the previous tick.
+//! - `tick_while_running()` sleeps between ticks until the signal is emitted,
+//!   or for at most 10ms, instead of ticking again immediately. The maximum
+//!   can be changed with `tick_while_running_with_sleep()`. `SyncTree` blocks
+//!   the current thread instead, with `WakeUpSignal::wait_blocking()`.
+
+use std::{
+    future::{self, Future},
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+
+/// Signal emitted by nodes to wake up the tree they belong to. Clones share
+/// the same signal.
+///
+/// Emitting the signal several times before it's received only wakes up the
+/// tree once.
+#[derive(Clone, Debug, Default)]
+pub struct WakeUpSignal {
+    state: Arc<Mutex<WakeUpState>>,
+}
+
+#[derive(Debug, Default)]
+struct WakeUpState {
+    emitted: bool,
+    /// Task waiting for the signal, if any
+    waker: Option<Waker>,
+}
+
+impl WakeUpSignal {
+    pub fn new() -> WakeUpSignal {
+        Self::default()
+    }
+
+    /// Wakes up the tree. Can be called from any thread.
+    pub fn emit(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.emitted = true;
+
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Returns `true` if the signal was emitted since it was last received,
+    /// and marks it as received.
+    pub fn take(&self) -> bool {
+        self.state
+            .lock()
+            .map(|mut state| std::mem::take(&mut state.emitted))
+            .unwrap_or(false)
+    }
+
+    /// Waits until the signal is emitted, and marks it as received. Returns
+    /// immediately if it was already emitted.
+    ///
+    /// Only one task can wait for the signal at a time. If another task
+    /// starts waiting, the previous one is only woken up by its next poll.
+    pub fn wait(&self) -> impl Future<Output = ()> + Send + '_ {
+        future::poll_fn(move |cx: &mut Context<'_>| {
+            let Ok(mut state) = self.state.lock() else {
+                return Poll::Ready(());
+            };
+
+            if std::mem::take(&mut state.emitted) {
+                Poll::Ready(())
+            } else {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Same as `wait()`, but gives up after `timeout`. Returns `true` if the
+    /// signal was emitted.
+    ///
+    /// Needs a tokio runtime with the time driver enabled.
+    pub async fn wait_for(&self, timeout: Duration) -> bool {
+        if self.take() {
+            return true;
+        }
+
+        tokio::time::timeout(timeout, self.wait()).await.is_ok()
+    }
+
+    /// Same as `wait_for()`, but blocks the current thread instead of
+    /// returning a future.
+    pub fn wait_blocking(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now().checked_add(timeout);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+
+        loop {
+            {
+                let Ok(mut state) = self.state.lock() else {
+                    return false;
+                };
+
+                if std::mem::take(&mut state.emitted) {
+                    return true;
+                }
+                state.waker = Some(waker.clone());
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                None => thread::park(),
+            }
+        }
+    }
+}
+
+/// Wakes up a thread blocked in `WakeUpSignal::wait_blocking()`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+use quick_xml::{
+    events::{BytesEnd, BytesStart, BytesText, Event},
+    Writer,
+};
+
+use crate::{
+    basic_types::{NodeType, PortDirection, TreeNodeManifest},
+    nodes::{PostCond, PreCond, TreeNodeBase, TreeNodePtr},
+    tree::ParseError,
+};
+
+/// ID used for the main tree when the root of an instantiated tree
+/// isn't a `SubTreeNode`, i.e. when the tree was built manually.
+pub const DEFAULT_MAIN_TREE_ID: &str = "MainTree";
+
+pub(crate) type XmlWriter = Writer<Vec<u8>>;
+
+pub(crate) fn new_writer() -> XmlWriter {
+    Writer::new_with_indent(Vec::new(), b' ', 4)
+}
+
+/// Writes the `<root>` start tag that wraps every document.
+pub(crate) fn write_root_start(
+    writer: &mut XmlWriter,
+    main_tree_id: Option<&str>,
+) -> Result<(), ParseError> {
+    let mut root = BytesStart::new("root");
+    root.push_attribute(("BTCPP_format", "4"));
+    if let Some(main_tree_id) = main_tree_id {
+        root.push_attribute(("main_tree_to_execute", main_tree_id));
+    }
+
+    writer.write_event(Event::Start(root))?;
+
+    Ok(())
+}
+
+/// Writes the `</root>` end tag and returns the contents of the document.
+pub(crate) fn finish_document(mut writer: XmlWriter) -> Result<String, ParseError> {
+    writer.write_event(Event::End(BytesEnd::new("root")))?;
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// Serializes an instantiated tree into a BehaviorTree.CPP (v4) XML document.
+///
+/// Every `SubTreeNode` in the tree is written as a `<SubTree>` reference, and
+/// the tree it references is written as a separate `<BehaviorTree>`. If the same
+/// tree ID is referenced more than once, it is only written once.
+pub fn write_tree_to_xml(root: &dyn TreeNodeBase) -> Result<String, ParseError> {
+    write_tree(root, false)
+}
+
+/// Same as `write_tree_to_xml()`, but every node has `_uid` and `_fullpath`
+/// attributes, and every SubTree instance is written as its own `<BehaviorTree>`,
+/// with a `_fullpath` attribute. The document also contains a `<TreeNodesModel>`
+/// with every node used by the tree, including built-in nodes.
+///
+/// This is the format expected by Groot2, e.g. in the header of `.btlog` files.
+pub fn write_tree_to_xml_with_metadata(root: &dyn TreeNodeBase) -> Result<String, ParseError> {
+    write_tree(root, true)
+}
+
+fn write_tree(root: &dyn TreeNodeBase, metadata: bool) -> Result<String, ParseError> {
+    let mut writer = new_writer();
+
+    let main_tree_id = match root.node_type() {
+        NodeType::SubTree => subtree_id(root),
+        _ => DEFAULT_MAIN_TREE_ID.to_string(),
+    };
+
+    write_root_start(&mut writer, Some(&main_tree_id))?;
+
+    let mut written = HashSet::new();
+    let mut pending = VecDeque::new();
+
+    // The root is either the wrapper of the main tree, or the first node of it
+    match root.node_type() {
+        NodeType::SubTree => pending.extend(
+            root.child_nodes()
+                .first()
+                .map(|c| (main_tree_id, String::new(), c)),
+        ),
+        _ => {
+            written.insert(main_tree_id.clone());
+            let instance = Instance {
+                tree_id: &main_tree_id,
+                path: "",
+                metadata,
+            };
+            write_behavior_tree(&mut writer, instance, root, &mut pending)?;
+        }
+    }
+
+    while let Some((tree_id, path, tree_root)) = pending.pop_front() {
+        // With metadata, every instance of a tree is written
+        if written.insert(tree_id.clone()) || metadata {
+            let instance = Instance {
+                tree_id: &tree_id,
+                path: &path,
+                metadata,
+            };
+            write_behavior_tree(&mut writer, instance, &**tree_root, &mut pending)?;
+        }
+    }
+
+    if metadata {
+        let mut manifests = Vec::new();
+        collect_manifests(root, &mut HashSet::new(), &mut manifests);
+        manifests.sort_by(|a, b| a.registration_id.cmp(&b.registration_id));
+
+        write_tree_nodes_model(&mut writer, manifests.iter().map(|m| &**m))?;
+    }
+
+    finish_document(writer)
+}
+
+/// Trees waiting to be written: `(tree_id, path of the SubTree, root)`
+type PendingTrees<'a> = VecDeque<(String, String, &'a TreeNodePtr)>;
+
+/// An instance of a `<BehaviorTree>` being written.
+#[derive(Clone, Copy)]
+struct Instance<'a> {
+    tree_id: &'a str,
+    /// Path of the `SubTreeNode` that instantiated the tree
+    path: &'a str,
+    /// Whether to write the `_uid` and `_fullpath` attributes
+    metadata: bool,
+}
+
+/// Collects the manifest of every node in the tree, except SubTrees.
+fn collect_manifests(
+    node: &dyn TreeNodeBase,
+    seen: &mut HashSet<String>,
+    manifests: &mut Vec<Arc<TreeNodeManifest>>,
+) {
+    if !matches!(node.node_type(), NodeType::SubTree) {
+        if let Ok(manifest) = node.config().manifest() {
+            if seen.insert(manifest.registration_id.clone()) {
+                manifests.push(manifest);
+            }
+        }
+    }
+
+    for child in node.child_nodes() {
+        collect_manifests(&**child, seen, manifests);
+    }
+}
+
+fn subtree_id(node: &dyn TreeNodeBase) -> String {
+    node.config()
+        .input_ports
+        .get("ID")
+        .cloned()
+        .unwrap_or_else(|| node.name().clone())
+}
+
+fn registration_id(node: &dyn TreeNodeBase) -> String {
+    match node.config().manifest() {
+        Ok(manifest) => manifest.registration_id.clone(),
+        Err(_) => node.name().clone(),
+    }
+}
+
+fn write_behavior_tree<'a>(
+    writer: &mut XmlWriter,
+    instance: Instance,
+    tree_root: &'a dyn TreeNodeBase,
+    pending: &mut PendingTrees<'a>,
+) -> Result<(), ParseError> {
+    let mut start = BytesStart::new("BehaviorTree");
+    start.push_attribute(("ID", instance.tree_id));
+    if instance.metadata {
+        start.push_attribute(("_fullpath", instance.path));
+    }
+    writer.write_event(Event::Start(start))?;
+
+    write_node(writer, tree_root, instance.metadata, pending)?;
+
+    writer.write_event(Event::End(BytesEnd::new("BehaviorTree")))?;
+
+    Ok(())
+}
+
+fn write_node<'a>(
+    writer: &mut XmlWriter,
+    node: &'a dyn TreeNodeBase,
+    metadata: bool,
+    pending: &mut PendingTrees<'a>,
+) -> Result<(), ParseError> {
+    let tag = registration_id(node);
+    let config = node.config();
+
+    let mut element = BytesStart::new(tag.as_str());
+
+    // Only write the instance name if it's different from the default
+    let default_name = match node.node_type() {
+        NodeType::SubTree => subtree_id(node),
+        _ => tag.clone(),
+    };
+    if node.name() != &tag && node.name() != &default_name {
+        element.push_attribute(("name", node.name().as_str()));
+    }
+
+    // Sort ports so the output is deterministic
+    let mut ports: Vec<(&String, &String)> = config
+        .input_ports
+        .iter()
+        .chain(config.output_ports.iter())
+        .collect();
+    ports.sort();
+
+    for (name, value) in ports {
+        element.push_attribute((name.as_str(), value.as_str()));
+    }
+
+    for condition in PreCond::ALL {
+        if let Some(script) = config.pre_condition(condition) {
+            element.push_attribute((condition.attribute_name(), script.source()));
+        }
+    }
+
+    for condition in PostCond::ALL {
+        if let Some(script) = config.post_condition(condition) {
+            element.push_attribute((condition.attribute_name(), script.source()));
+        }
+    }
+
+    if metadata {
+        element.push_attribute(("_uid", config.uid.to_string().as_str()));
+        element.push_attribute(("_fullpath", config.path.as_str()));
+    }
+
+    if matches!(node.node_type(), NodeType::SubTree) {
+        if let Some(child) = node.child_nodes().first() {
+            pending.push_back((subtree_id(node), config.path.clone(), child));
+        }
+
+        writer.write_event(Event::Empty(element))?;
+        return Ok(());
+    }
+
+    let children = node.child_nodes();
+
+    if children.is_empty() {
+        writer.write_event(Event::Empty(element))?;
+    } else {
+        writer.write_event(Event::Start(element))?;
+
+        for child in children {
+            write_node(writer, &**child, metadata, pending)?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::new(tag)))?;
+    }
+
+    Ok(())
+}
+
+/// Writes a `<TreeNodesModel>` section containing one entry per manifest.
+pub(crate) fn write_tree_nodes_model<'a>(
+    writer: &mut XmlWriter,
+    manifests: impl Iterator<Item = &'a TreeNodeManifest>,
+) -> Result<(), ParseError> {
+    writer.write_event(Event::Start(BytesStart::new("TreeNodesModel")))?;
+
+    for manifest in manifests {
+        let tag = manifest.node_type.to_string();
+
+        let mut element = BytesStart::new(tag.as_str());
+        element.push_attribute(("ID", manifest.registration_id.as_str()));
+        if !manifest.description.is_empty() {
+            element.push_attribute(("description", manifest.description.as_str()));
+        }
+
+        if manifest.ports.is_empty() {
+            writer.write_event(Event::Empty(element))?;
+            continue;
+        }
+
+        writer.write_event(Event::Start(element))?;
+
+        // Sort ports so the output is deterministic
+        let mut ports: Vec<_> = manifest.ports.iter().collect();
+        ports.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (port_name, port_info) in ports {
+            let port_tag = match port_info.direction() {
+                PortDirection::Input => "input_port",
+                PortDirection::Output => "output_port",
+                PortDirection::InOut => "inout_port",
+            };
+
+            let mut port = BytesStart::new(port_tag);
+            port.push_attribute(("name", port_name.as_str()));
+            if let Some(default) = port_info.default_value() {
+                port.push_attribute(("default", default.as_str()));
+            }
+
+            if port_info.description().is_empty() {
+                writer.write_event(Event::Empty(port))?;
+            } else {
+                writer.write_event(Event::Start(port))?;
+                writer.write_event(Event::Text(BytesText::new(port_info.description())))?;
+                writer.write_event(Event::End(BytesEnd::new(port_tag)))?;
+            }
+        }
+
+        writer.write_event(Event::End(BytesEnd::new(tag)))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("TreeNodesModel")))?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use behaviortree_rs::{
+    basic_types::{NodeStatus, NodeType},
+    blackboard::Blackboard,
+    macros::register_action_node,
+    nodes::NodeError,
+    scripting::ScriptError,
+    tree::Factory,
+};
+
+mod nodes;
+
+use nodes::StatusNode;
+
+#[test]
+fn script() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Script code="counter := 0; name := 'robot'" />
+                    <Script code="counter += 2; name = name .. '_' .. counter" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact_sync::<i64>("counter"), Some(2));
+    assert_eq!(
+        blackboard.get_exact_sync::<String>("name"),
+        Some(String::from("robot_2"))
+    );
+
+    // The code is read from the port on every tick
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Script code="{code}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    blackboard.set_sync("code", String::from("counter = 5"));
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact_sync::<i64>("counter"), Some(5));
+
+    blackboard.set_sync("code", String::from("counter = 'text'"));
+
+    assert!(matches!(
+        tree.tick_while_running(),
+        Err(NodeError::ScriptError(
+            _,
+            ScriptError::InvalidConversion(..)
+        ))
+    ));
+
+    blackboard.set_sync("code", String::from("counter = "));
+
+    assert!(matches!(
+        tree.tick_while_running(),
+        Err(NodeError::ScriptError(_, ScriptError::UnexpectedEnd(_)))
+    ));
+}
+
+#[test]
+fn script_condition() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <ScriptCondition code="battery &gt; 20 &amp;&amp; !charging" />
+                    <StatusNode status="Failure" />
+                </Fallback>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("battery", 50);
+    blackboard.set_sync("charging", false);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    blackboard.set_sync("battery", 10);
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    blackboard.set_sync("battery", String::from("empty"));
+
+    assert!(matches!(
+        tree.tick_while_running(),
+        Err(NodeError::ScriptError(
+            _,
+            ScriptError::InvalidOperands(">", "string", "integer")
+        ))
+    ));
+
+    assert_eq!(
+        factory.manifest("ScriptCondition").unwrap().node_type,
+        NodeType::Condition
+    );
+    assert_eq!(
+        factory.manifest("Script").unwrap().node_type,
+        NodeType::Action
+    );
+}
@@ -0,0 +1,180 @@
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    macros::register_action_node,
+    scripting::ScriptError,
+    tree::{Factory, ParseError},
+};
+
+mod nodes;
+
+use nodes::{RunForNode, StatusNode};
+
+#[test]
+fn pre_conditions() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Failure" _skipIf="skip" />
+                    <StatusNode status="Success" _successIf="count &gt;= 3" />
+                    <StatusNode status="Success" _failureIf="count &lt; 3 &amp;&amp; !skip" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("skip", true);
+    blackboard.set_sync("count", 5);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap();
+
+    // The first node is skipped, the second succeeds without being ticked
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    blackboard.set_sync("skip", false);
+    blackboard.set_sync("count", 1);
+
+    // The first node is ticked and fails
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    // Conditions that fail to execute return an error
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert!(tree.tick_while_running().is_err());
+
+    blackboard.set_sync("skip", true);
+    blackboard.set_sync("count", 3);
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn post_conditions() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence _onSuccess="result := 'success'" _onFailure="result := 'failure'">
+                    <StatusNode status="Success" _post="ticks := 1" />
+                    <StatusNode status="{status}" _onSuccess="succeeded = true" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("status", NodeStatus::Success);
+    blackboard.set_sync("succeeded", false);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<i64>("ticks"), Some(1));
+    assert_eq!(blackboard.get_sync::<bool>("succeeded"), Some(true));
+    assert_eq!(
+        blackboard.get_sync::<String>("result"),
+        Some(String::from("success"))
+    );
+
+    blackboard.set_sync("status", NodeStatus::Failure);
+    blackboard.set_sync("succeeded", false);
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get_sync::<bool>("succeeded"), Some(false));
+    assert_eq!(
+        blackboard.get_sync::<String>("result"),
+        Some(String::from("failure"))
+    );
+}
+
+#[test]
+fn while_condition_halts_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <RunForNode iters="10" _while="keep_running" _onHalted="halted := true" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("keep_running", true);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), None);
+
+    blackboard.set_sync("keep_running", false);
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+
+    // The condition is checked before starting the node again
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+}
+
+#[test]
+fn invalid_condition_scripts() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode status="Success" _skipIf="count &gt;" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    factory.register_bt_from_text(xml).unwrap();
+
+    let diagnostics = factory.validate("main");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0].error,
+        ParseError::InvalidScript(attr, ScriptError::UnexpectedEnd(_)) if attr == "_skipIf"
+    ));
+
+    let err = factory
+        .instantiate_sync_tree(&Blackboard::create(), "main")
+        .unwrap_err();
+
+    assert!(matches!(
+        err.inner(),
+        ParseError::InvalidScript(attr, ScriptError::UnexpectedEnd(_)) if attr == "_skipIf"
+    ));
+    assert_eq!(err.location().unwrap().line, 4);
+}
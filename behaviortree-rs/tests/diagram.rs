@@ -0,0 +1,130 @@
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    diagram::{DiagramOptions, SubTreeStyle},
+};
+
+mod nodes;
+
+const XML: &str = r#"
+    <root main_tree_to_execute="main">
+        <BehaviorTree ID="main">
+            <Fallback>
+                <StatusNode name="first" status="Failure" />
+                <Inverter>
+                    <SubTree ID="sub" name="child" />
+                </Inverter>
+            </Fallback>
+        </BehaviorTree>
+
+        <BehaviorTree ID="sub">
+            <RunForNode iters="3" />
+        </BehaviorTree>
+    </root>
+"#;
+
+#[test]
+fn dot_export() {
+    nodes::test_setup();
+
+    let mut factory = nodes::test_factory();
+    let mut tree = factory
+        .create_sync_tree_from_text(XML.to_string(), &Blackboard::create())
+        .unwrap();
+
+    assert_eq!(
+        tree.to_dot(&DiagramOptions::default()),
+        r#"digraph "main" {
+    node [fontname="Helvetica"];
+    n1 [label="main", shape=box, peripheries=2];
+    n2 [label="Fallback", shape=box];
+    n3 [label="first\nStatusNode", shape=ellipse];
+    n4 [label="Inverter", shape=diamond];
+    n5 [label="child\nsub", shape=box, peripheries=2];
+    n6 [label="RunForNode", shape=ellipse];
+    n1 -> n2;
+    n2 -> n3;
+    n2 -> n4;
+    n4 -> n5;
+    n5 -> n6;
+}
+"#
+    );
+
+    assert_eq!(tree.tick_exactly_once().unwrap(), NodeStatus::Running);
+
+    let options = DiagramOptions {
+        status_colors: true,
+        subtrees: SubTreeStyle::Cluster,
+    };
+    assert_eq!(
+        tree.to_dot(&options),
+        r##"digraph "main" {
+    node [fontname="Helvetica"];
+    subgraph cluster_1 {
+        label="main";
+        style=filled;
+        fillcolor="#f9e79f";
+        n2 [label="Fallback", shape=box, style=filled, fillcolor="#f9e79f"];
+        n3 [label="first\nStatusNode", shape=ellipse, style=filled, fillcolor="#f5b7b1"];
+        n4 [label="Inverter", shape=diamond, style=filled, fillcolor="#f9e79f"];
+        subgraph cluster_5 {
+            label="child\nsub";
+            style=filled;
+            fillcolor="#f9e79f";
+            n6 [label="RunForNode", shape=ellipse, style=filled, fillcolor="#f9e79f"];
+        }
+    }
+    n2 -> n3;
+    n2 -> n4;
+    n4 -> n6;
+}
+"##
+    );
+}
+
+#[test]
+fn mermaid_export() {
+    nodes::test_setup();
+
+    let mut factory = nodes::test_factory();
+    factory.register_bt_from_text(XML.to_string()).unwrap();
+
+    assert_eq!(
+        factory
+            .write_tree_to_mermaid("main", &DiagramOptions::default())
+            .unwrap(),
+        r#"flowchart TD
+    n1[["main"]]
+    n2["Fallback"]
+    n3(["first<br/>StatusNode"])
+    n4{"Inverter"}
+    n5[["child<br/>sub"]]
+    n6(["RunForNode"])
+    n1 --> n2
+    n2 --> n3
+    n2 --> n4
+    n4 --> n5
+    n5 --> n6
+"#
+    );
+
+    let options = DiagramOptions {
+        subtrees: SubTreeStyle::Cluster,
+        ..Default::default()
+    };
+    assert_eq!(
+        factory.write_tree_to_mermaid("sub", &options).unwrap(),
+        r#"flowchart TD
+    subgraph cluster_1 ["sub"]
+        n2(["RunForNode"])
+    end
+"#
+    );
+
+    assert!(factory
+        .write_tree_to_dot("main", &options)
+        .unwrap()
+        .contains("subgraph cluster_5 {"));
+    assert!(factory.write_tree_to_dot("unknown", &options).is_err());
+}
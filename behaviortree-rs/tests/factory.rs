@@ -1,8 +1,11 @@
 use behaviortree_rs::{
-    basic_types::NodeStatus, blackboard::Blackboard, macros::register_action_node, tree::Factory,
+    basic_types::{NodeStatus, NodeType},
+    blackboard::Blackboard,
+    macros::{build_node_ptr, register_action_node},
+    tree::{Factory, ParseError},
 };
 
-use crate::nodes::{DataNode, EchoNode, StatusNode};
+use crate::nodes::{DataNode, EchoNode, RunForNode, StatusNode};
 
 mod nodes;
 
@@ -238,6 +241,37 @@ fn ignore_treenodesmodel() {
     assert!(tree.is_ok());
 }
 
+#[test]
+fn parse_treenodesmodel() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+
+            <TreeNodesModel>
+                <Action ID="StatusNode"></Action>
+            </TreeNodesModel>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let blackboard = Blackboard::create();
+    let tree = factory.create_sync_tree_from_text(xml, &blackboard);
+
+    if tree.is_err() {
+        log::error!("{}", tree.as_ref().err().unwrap());
+    }
+
+    assert!(tree.is_ok());
+}
+
 #[test]
 fn load_adjacent_controls() {
     let _ = pretty_env_logger::formatted_builder()
@@ -334,3 +368,506 @@ fn async_test() {
         assert!(res.is_ok());
     });
 }
+
+fn tree_file(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("trees")
+        .join(name)
+}
+
+#[test]
+fn register_from_file_with_includes() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    // main.xml includes shared/subtrees.xml, which includes ../leaves.xml
+    factory
+        .register_bt_from_file(tree_file("main.xml"))
+        .unwrap();
+
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "succeed")
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    // Includes from text are resolved from the working directory. Absolute paths are
+    // used as-is, and the main tree of an included file is ignored
+    let xml = format!(
+        r#"
+        <root main_tree_to_execute="main">
+            <include path="{}" />
+
+            <BehaviorTree ID="main">
+                <SubTree ID="fail" />
+            </BehaviorTree>
+        </root>
+        "#,
+        tree_file("shared/subtrees.xml").display()
+    );
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+}
+
+#[test]
+fn invalid_includes() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    let err = factory
+        .register_bt_from_file(tree_file("cycle_a.xml"))
+        .unwrap_err();
+    assert!(matches!(err.inner(), ParseError::CircularInclude(_)));
+
+    let mut factory = Factory::new();
+    let err = factory
+        .register_bt_from_file(tree_file("missing_include.xml"))
+        .unwrap_err();
+    match err.inner() {
+        ParseError::FileError(path, _) => assert!(path.ends_with("does_not_exist.xml")),
+        other => panic!("Expected FileError, got {other:?}"),
+    }
+
+    // The error points at the <include> tag
+    let location = err.location().unwrap();
+    assert_eq!(location.file, Some(tree_file("missing_include.xml")));
+    assert_eq!((location.line, location.column), (3, 5));
+
+    let mut factory = Factory::new();
+    assert!(matches!(
+        factory.register_bt_from_file(tree_file("does_not_exist.xml")),
+        Err(ParseError::FileError(_, _))
+    ));
+
+    let xml = r#"
+        <root>
+            <include />
+        </root>
+    "#
+    .to_string();
+
+    assert!(matches!(
+        Factory::new()
+            .register_bt_from_text(xml)
+            .unwrap_err()
+            .inner(),
+        ParseError::MissingAttribute(_)
+    ));
+}
+
+#[test]
+fn validate() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" name="ok" />
+                    <SubTree ID="valid" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="valid">
+                <Inverter>
+                    <StatusNode status="Failure" />
+                </Inverter>
+            </BehaviorTree>
+
+            <BehaviorTree ID="invalid">
+                <Sequence>
+                    <UnknownNode />
+                    <SubTree ID="missing" />
+                    <SubTree />
+                    <Inverter>
+                        <StatusNode status="Success" />
+                        <StatusNode status="Success" />
+                    </Inverter>
+                    <StatusNode status="Success">
+                        <StatusNode status="Success" />
+                    </StatusNode>
+                    <StatusNode status="Success" foo="bar" />
+                    <SubTree ID="recursive" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="recursive">
+                <SubTree ID="invalid" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.register_bt_from_text(xml).unwrap();
+
+    assert!(factory.validate("main").is_empty());
+
+    let diagnostics = factory.validate("invalid");
+
+    let errors: Vec<(&str, &str, &ParseError)> = diagnostics
+        .iter()
+        .map(|d| (d.tree_id.as_str(), d.node_path.as_str(), &d.error))
+        .collect();
+
+    assert_eq!(errors.len(), 7);
+    assert!(matches!(
+        errors[0],
+        (
+            "invalid",
+            "Sequence/UnknownNode",
+            ParseError::UnknownNode(_)
+        )
+    ));
+    assert!(matches!(
+        errors[1],
+        ("invalid", "Sequence/SubTree", ParseError::UnknownTree(id)) if id == "missing"
+    ));
+    assert!(matches!(
+        errors[2],
+        (
+            "invalid",
+            "Sequence/SubTree",
+            ParseError::MissingAttribute(_)
+        )
+    ));
+    assert!(matches!(
+        errors[3],
+        (
+            "invalid",
+            "Sequence/Inverter",
+            ParseError::InvalidChildCount(_, _)
+        )
+    ));
+    assert!(matches!(
+        errors[4],
+        (
+            "invalid",
+            "Sequence/StatusNode",
+            ParseError::InvalidChildCount(_, _)
+        )
+    ));
+    assert!(matches!(
+        errors[5],
+        ("invalid", "Sequence/StatusNode", ParseError::InvalidPort(port, _, _)) if port == "foo"
+    ));
+    assert!(matches!(
+        errors[6],
+        ("recursive", "SubTree", ParseError::RecursiveSubTree(id)) if id == "invalid"
+    ));
+
+    // Instantiation fails on the first of them
+    assert!(factory
+        .instantiate_sync_tree(&Blackboard::create(), "invalid")
+        .is_err());
+
+    let diagnostics = factory.validate("missing");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(diagnostics[0].error, ParseError::UnknownTree(_)));
+}
+
+#[test]
+fn register_node_by_hand() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode status="Success" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    factory.register_node(
+        "StatusNode",
+        |config, _children| build_node_ptr!(config, "StatusNode", StatusNode),
+        NodeType::Action,
+    );
+    factory.register_bt_from_text(xml).unwrap();
+
+    // The ports aren't known until the node is built
+    let manifest = factory.manifest("StatusNode").unwrap();
+    assert_eq!(manifest.node_type, NodeType::Action);
+    assert!(manifest.ports.is_empty());
+    assert!(factory.validate("main").is_empty());
+
+    let mut tree = factory
+        .instantiate_sync_tree(&Blackboard::create(), "main")
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn error_locations() {
+    nodes::test_setup();
+
+    let xml = r#"<root main_tree_to_execute="main">
+    <BehaviorTree ID="main">
+        <Sequence>
+            <SubTree ID="sub" name="child" />
+        </Sequence>
+    </BehaviorTree>
+
+    <BehaviorTree ID="sub">
+        <Inverter>
+            <UnknownNode />
+        </Inverter>
+    </BehaviorTree>
+</root>"#
+        .to_string();
+
+    let mut factory = Factory::new();
+    let err = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap_err();
+
+    assert!(matches!(err.inner(), ParseError::UnknownNode(_)));
+
+    let location = err.location().unwrap();
+    assert_eq!(location.file, None);
+    assert_eq!((location.line, location.column), (10, 13));
+    assert_eq!(location.tree_id.as_deref(), Some("sub"));
+    assert_eq!(
+        location.node_path.as_deref(),
+        Some("main/child/Inverter/UnknownNode")
+    );
+
+    assert_eq!(
+        err.to_string(),
+        "<text>:10:13 in tree [sub] at node [main/child/Inverter/UnknownNode]: \
+         Attempted to parse node with unregistered name: UnknownNode"
+    );
+
+    let expected = r#"error: Attempted to parse node with unregistered name: UnknownNode
+  --> <text>:10:13
+   |
+10 |             <UnknownNode />
+   |             ^^^^^^^^^^^^"#;
+    assert_eq!(err.snippet().unwrap(), expected);
+
+    // Errors in files include the file name
+    let path = tree_file("invalid_port.xml");
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.register_bt_from_file(&path).unwrap();
+
+    let err = factory
+        .instantiate_sync_tree(&Blackboard::create(), "invalid_port")
+        .unwrap_err();
+
+    assert!(matches!(err.inner(), ParseError::InvalidPort(_, _, _)));
+
+    let location = err.location().unwrap();
+    assert_eq!(location.file, Some(path.canonicalize().unwrap()));
+    assert_eq!((location.line, location.column), (6, 13));
+    assert_eq!(location.tree_id.as_deref(), Some("invalid_port"));
+    assert_eq!(
+        location.node_path.as_deref(),
+        Some("invalid_port/Sequence/StatusNode")
+    );
+
+    // Node type mismatches include the type
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode status="Success"></StatusNode>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let err = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap_err();
+
+    assert_eq!(
+        err.inner().to_string(),
+        "Node type [Action] has an invalid number of children."
+    );
+}
+
+#[test]
+fn node_paths_and_uids() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence name="root_sequence">
+                    <StatusNode name="first" status="Success" />
+                    <SubTree ID="sub" name="child" />
+                    <SubTree ID="sub" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Inverter>
+                    <StatusNode status="Failure" />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    // UIDs are assigned in depth-first order
+    let expected = [
+        "main",
+        "main/root_sequence",
+        "main/root_sequence/first",
+        "main/child",
+        "main/child/Inverter",
+        "main/child/Inverter/StatusNode",
+        "main/sub::7",
+        "main/sub::7/Inverter",
+        "main/sub::7/Inverter/StatusNode",
+    ];
+
+    for (uid, path) in (1..).zip(expected) {
+        let node = tree.node_by_uid(uid).unwrap();
+        assert_eq!(node.path(), path);
+        assert_eq!(tree.node_by_path(path).unwrap().config().uid, uid);
+    }
+
+    assert!(tree.node_by_uid(expected.len() as u16 + 1).is_none());
+    assert!(tree.node_by_path("main/missing").is_none());
+    assert_eq!(
+        tree.node_by_path("main/root_sequence/first")
+            .unwrap()
+            .name(),
+        "first"
+    );
+
+    // UIDs start again for every tree
+    let other = factory
+        .instantiate_sync_tree(&Blackboard::create(), "sub")
+        .unwrap();
+    assert_eq!(other.root().config().uid, 1);
+    assert_eq!(
+        other.node_by_uid(3).unwrap().path(),
+        "sub/Inverter/StatusNode"
+    );
+}
+
+#[test]
+fn visit_tree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <Inverter>
+                        <SubTree ID="sub" />
+                    </Inverter>
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    let mut visited = Vec::new();
+    tree.visit(|node, depth| visited.push((node.config().uid, depth)));
+    assert_eq!(visited, [(1, 0), (2, 1), (3, 2), (4, 2), (5, 3), (6, 4)]);
+
+    tree.visit_mut(|node, depth| node.set_name(format!("{}_{depth}", node.name())));
+
+    let mut names = Vec::new();
+    tree.visit(|node, _| names.push(node.name().clone()));
+    assert_eq!(
+        names,
+        [
+            "main_0",
+            "Sequence_1",
+            "StatusNode_2",
+            "Inverter_2",
+            "sub_3",
+            "StatusNode_4"
+        ]
+    );
+}
+
+#[test]
+fn display_tree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <StatusNode name="first" status="Failure" />
+                    <Inverter>
+                        <SubTree ID="sub" />
+                    </Inverter>
+                </Fallback>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <RunForNode iters="3" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    assert_eq!(tree.tick_exactly_once().unwrap(), NodeStatus::Running);
+
+    println!("{tree}");
+    assert_eq!(
+        format!("{tree:#}"),
+        r#"main (SubTree) ID="main" [RUNNING]
+└── Fallback [RUNNING]
+    ├── first (StatusNode) status="Failure" [FAILURE]
+    └── Inverter [RUNNING]
+        └── sub (SubTree) ID="sub" [RUNNING]
+            └── RunForNode iters="3" status="SUCCESS" [RUNNING]
+"#
+    );
+
+    assert!(tree.to_string().contains("\x1b[33mRUNNING\x1b[0m"));
+}
@@ -0,0 +1,607 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    loggers::{
+        BtLog, BtLogError, ChromeTrace, FileLogger2, PrometheusExporter, StatusChangeLogger,
+        StdoutLogger, TreeStatistics,
+    },
+    macros::register_action_node,
+    nodes::{NodeConfig, TreeNodeBase},
+    tree::Factory,
+};
+
+mod nodes;
+
+use nodes::{RunForNode, StatusNode};
+
+#[derive(Default)]
+struct RecordingLogger {
+    transitions: Vec<(String, NodeStatus, NodeStatus)>,
+    timestamps: Vec<Duration>,
+    flushed: bool,
+}
+
+impl StatusChangeLogger for RecordingLogger {
+    fn on_status_change(
+        &mut self,
+        timestamp: Duration,
+        node: &dyn TreeNodeBase,
+        prev_status: NodeStatus,
+        status: NodeStatus,
+    ) {
+        self.transitions
+            .push((node.name().clone(), prev_status, status));
+        self.timestamps.push(timestamp);
+    }
+
+    fn flush(&mut self) {
+        self.flushed = true;
+    }
+}
+
+#[test]
+fn status_change_logger() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode name="first" status="Success" />
+                    <StatusNode name="second" status="{status}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("status", NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let logger = Arc::new(Mutex::new(RecordingLogger::default()));
+    let other = Arc::new(Mutex::new(RecordingLogger::default()));
+    tree.add_logger(logger.clone());
+    tree.add_logger(other.clone());
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    use NodeStatus::*;
+    let expected: Vec<(String, NodeStatus, NodeStatus)> = [
+        ("Sequence", Idle, Running),
+        ("first", Idle, Success),
+        ("second", Idle, Failure),
+        ("first", Success, Idle),
+        ("second", Failure, Idle),
+        ("Sequence", Running, Failure),
+        ("Sequence", Failure, Idle),
+        ("main", Idle, Failure),
+        ("main", Failure, Idle),
+    ]
+    .into_iter()
+    .map(|(name, prev, status)| (name.to_string(), prev, status))
+    .collect();
+
+    assert_eq!(logger.lock().unwrap().transitions, expected);
+
+    let timestamps = logger.lock().unwrap().timestamps.clone();
+    assert!(timestamps.windows(2).all(|t| t[0] <= t[1]));
+
+    // Every logger receives every transition
+    assert_eq!(
+        other.lock().unwrap().transitions,
+        logger.lock().unwrap().transitions
+    );
+
+    tree.clear_loggers();
+    assert!(logger.lock().unwrap().flushed);
+
+    // Removed loggers aren't notified anymore
+    tree.tick_while_running().unwrap();
+    assert_eq!(logger.lock().unwrap().transitions.len(), 9);
+}
+
+#[test]
+fn stdout_logger() {
+    nodes::test_setup();
+
+    let mut config = NodeConfig::new(Blackboard::create());
+    config.path = String::from("main/Sequence/first");
+    let node = StatusNode::new("first", config);
+
+    let logger = StdoutLogger::new();
+    let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+    let line = logger
+        .format_transition(
+            start + Duration::from_millis(1500),
+            &node,
+            &NodeStatus::Idle,
+            &NodeStatus::Success,
+        )
+        .unwrap();
+
+    assert!(line.contains("main/Sequence/first"));
+    assert!(line.contains(&format!(
+        "{} -> {}",
+        NodeStatus::Idle.into_string_color(),
+        NodeStatus::Success.into_string_color()
+    )));
+    // Elapsed time since the logger was created
+    assert!(line.starts_with("[    1.5"));
+
+    // Transitions to Idle can be hidden
+    let idle = (&NodeStatus::Success, &NodeStatus::Idle);
+    assert!(logger
+        .format_transition(start, &node, idle.0, idle.1)
+        .is_some());
+
+    let logger = logger.show_idle_transitions(false);
+    assert!(logger
+        .format_transition(start, &node, idle.0, idle.1)
+        .is_none());
+    assert!(logger
+        .format_transition(start, &node, &NodeStatus::Idle, &NodeStatus::Running)
+        .is_some());
+}
+
+#[test]
+fn file_logger2() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode name="first" status="Success" />
+                    <SubTree ID="sub" />
+                </Sequence>
+            </BehaviorTree>
+            <BehaviorTree ID="sub">
+                <StatusNode name="second" status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let path = std::env::temp_dir().join("behaviortree_rs_file_logger2.btlog");
+    let logger = FileLogger2::new(tree.root(), &path).unwrap();
+    tree.add_logger(logger);
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+    tree.clear_loggers();
+
+    let log = BtLog::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(log.xml.contains("<TreeNodesModel>"));
+    assert!(log.xml.contains(r#"<BehaviorTree ID="sub""#));
+
+    // UIDs are assigned depth-first, the root being the wrapper of "main"
+    let mut uids: Vec<u16> = log.node_paths().unwrap().into_keys().collect();
+    uids.sort();
+    assert_eq!(uids, [2, 3, 4, 5]);
+
+    use NodeStatus::*;
+    let recorded: Vec<(u16, NodeStatus)> = log
+        .transitions
+        .iter()
+        .map(|t| (t.uid, t.status.clone()))
+        .collect();
+
+    assert_eq!(recorded[..3], [(2, Running), (3, Success), (5, Failure)]);
+    assert!(log
+        .transitions
+        .windows(2)
+        .all(|t| t[0].timestamp <= t[1].timestamp));
+
+    // Incomplete transitions at the end are ignored
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"BTCPP4-FileLogger2\x01");
+    bytes.extend_from_slice(&0i32.to_le_bytes());
+    bytes.extend_from_slice(&0i64.to_le_bytes());
+    bytes.extend_from_slice(&[1, 0, 0, 0, 0, 0, 7, 0, 2, 0xff]);
+
+    let log = BtLog::from_bytes(&bytes).unwrap();
+    assert_eq!(log.transitions.len(), 1);
+    assert_eq!(log.transitions[0].uid, 7);
+    assert_eq!(log.transitions[0].status, Success);
+    assert_eq!(log.transitions[0].timestamp, Duration::from_micros(1));
+
+    assert!(matches!(
+        BtLog::from_bytes(b"not a log"),
+        Err(BtLogError::InvalidHeader)
+    ));
+    assert!(matches!(
+        FileLogger2::new(tree.root(), "run.log"),
+        Err(BtLogError::InvalidExtension(_))
+    ));
+}
+
+#[test]
+fn chrome_trace() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode name="first" status="Success" />
+                    <StatusNode name="second" status="Success" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap();
+    let mut other = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    // Streaming, with one track per tree
+    let path = std::env::temp_dir().join("behaviortree_rs_chrome_trace.json");
+    let trace = ChromeTrace::stream_to_file(&path).unwrap();
+    tree.add_logger(trace.profiler("robot \"a\""));
+    other.add_logger(trace.profiler("robot_b"));
+    drop(trace);
+
+    tree.tick_while_running().unwrap();
+    tree.tick_while_running().unwrap();
+    other.tick_while_running().unwrap();
+
+    // The file is complete when every profiler is dropped
+    drop(tree);
+    drop(other);
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(json.starts_with('['));
+    assert!(json.trim_end().ends_with(']'));
+    assert!(json.contains(r#""args":{"name":"robot \"a\""}"#));
+    assert!(json.contains(r#""tid":2,"args":{"name":"robot_b"}"#));
+
+    // main, Sequence, first and second, for 3 ticks
+    assert_eq!(json.matches(r#""ph":"B""#).count(), 12);
+    assert_eq!(json.matches(r#""ph":"E""#).count(), 12);
+    assert_eq!(json.matches(r#""name":"first","cat":"Action""#).count(), 3);
+    assert_eq!(json.matches(r#""status":"SUCCESS""#).count(), 12);
+
+    // Slices are nested: parents begin before their children
+    let first_begin = json.find(r#""name":"first""#).unwrap();
+    let sequence_begin = json.find(r#""name":"Sequence""#).unwrap();
+    assert!(sequence_begin < first_begin);
+
+    // One file per tick
+    let dir = std::env::temp_dir().join("behaviortree_rs_chrome_trace");
+    let trace = ChromeTrace::per_tick(&dir).unwrap();
+
+    let mut tree = factory
+        .create_sync_tree_from_text(
+            r#"
+                <root>
+                    <BehaviorTree ID="main">
+                        <StatusNode status="Success" />
+                    </BehaviorTree>
+                </root>
+            "#
+            .to_string(),
+            &blackboard,
+        )
+        .unwrap();
+    tree.add_logger(trace.profiler("main"));
+
+    tree.tick_while_running().unwrap();
+    tree.tick_while_running().unwrap();
+
+    for tick in 0..2 {
+        let json = std::fs::read_to_string(dir.join(format!("main_{tick}.json"))).unwrap();
+        assert!(json.contains(r#""name":"thread_name""#));
+        assert_eq!(json.matches(r#""ph":"B""#).count(), 2);
+    }
+    assert!(!dir.join("main_2.json").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tree_statistics() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <StatusNode status="Failure" />
+                    <RunForNode iters="2" _while="keep_running" />
+                </Fallback>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("keep_running", true);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let statistics = TreeStatistics::new();
+    tree.add_logger(statistics.clone());
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+
+    let status_node = statistics.get("main/Fallback/StatusNode").unwrap();
+    assert_eq!(status_node.ticks, 2);
+    assert_eq!(status_node.failure_count, 2);
+    assert_eq!(status_node.running_count, 0);
+
+    let run_for = statistics.get("main/Fallback/RunForNode").unwrap();
+    assert_eq!(run_for.success_count, 1);
+    assert_eq!(run_for.running_count, 2);
+    assert_eq!(run_for.halt_count, 0);
+    assert!(run_for.min_tick_duration <= run_for.mean_tick_duration());
+    assert!(run_for.mean_tick_duration() <= run_for.max_tick_duration);
+
+    // Stopping the _while condition halts the running node
+    blackboard.set_sync("keep_running", false);
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    let run_for = statistics.get("main/Fallback/RunForNode").unwrap();
+    assert_eq!(run_for.halt_count, 1);
+    assert_eq!(run_for.skip_count, 1);
+
+    let table = statistics.to_string();
+    let header = table.lines().next().unwrap();
+    assert!(header.starts_with("Path"));
+    assert!(header.contains("Halted"));
+    assert!(table.contains("main/Fallback/RunForNode"));
+    assert_eq!(table.lines().count(), statistics.snapshot().len() + 1);
+
+    statistics.reset();
+    assert!(statistics.snapshot().is_empty());
+}
+
+#[test]
+fn tree_statistics_same_path() {
+    nodes::test_setup();
+
+    // Both StatusNodes have the path main/Sequence/StatusNode
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <StatusNode status="Failure" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    let statistics = TreeStatistics::new();
+    tree.add_logger(statistics.clone());
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    let path = "main/Sequence/StatusNode".to_string();
+    let mut uids = Vec::new();
+    tree.visit(|node, _| {
+        if *node.path() == path {
+            uids.push(node.config().uid);
+        }
+    });
+    assert_eq!(uids.len(), 2);
+
+    let first = statistics.get_by_uid(uids[0]).unwrap();
+    assert_eq!((first.ticks, first.success_count), (1, 1));
+    let second = statistics.get_by_uid(uids[1]).unwrap();
+    assert_eq!((second.ticks, second.failure_count), (1, 1));
+
+    // The path alone finds the first one
+    assert_eq!(statistics.get(&path), Some(first));
+
+    let snapshot = statistics.snapshot();
+    assert!(snapshot.contains_key(&(path.clone(), uids[0])));
+    assert!(snapshot.contains_key(&(path, uids[1])));
+}
+
+fn metrics_tree(factory: &mut Factory, blackboard: &Blackboard) -> behaviortree_rs::tree::SyncTree {
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Script code="count := 1" />
+                    <StatusNode status="{status}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    factory.create_sync_tree_from_text(xml, blackboard).unwrap()
+}
+
+#[test]
+fn prometheus_exporter() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("status", NodeStatus::Success);
+
+    let mut tree = metrics_tree(&mut factory, &blackboard);
+
+    let exporter = PrometheusExporter::new();
+    tree.add_logger(exporter.tree_logger("robot \"a\""));
+
+    tree.tick_while_running().unwrap();
+    blackboard.set_sync("status", NodeStatus::Failure);
+    tree.tick_while_running().unwrap();
+
+    let mut uids = HashMap::new();
+    tree.visit(|node, _| {
+        uids.insert(node.path().clone(), node.config().uid);
+    });
+    let status_uid = uids["main/Sequence/StatusNode"];
+    let sequence_uid = uids["main/Sequence"];
+
+    let metrics = exporter.render();
+    let tree_label = r#"tree="robot \"a\"""#;
+
+    assert!(metrics.contains("# TYPE behaviortree_tree_ticks_total counter"));
+    assert!(metrics.contains(&format!("behaviortree_tree_ticks_total{{{tree_label}}} 2")));
+    // The "status" and "count" entries
+    assert!(metrics.contains(&format!(
+        "behaviortree_blackboard_entries{{{tree_label}}} 2"
+    )));
+    assert!(metrics.contains(&format!(
+        r#"behaviortree_node_ticks_total{{{tree_label},path="main/Sequence/StatusNode",uid="{status_uid}",id="StatusNode"}} 2"#
+    )));
+
+    for result in ["success", "failure"] {
+        assert!(metrics.contains(&format!(
+            r#"behaviortree_node_results_total{{{tree_label},path="main/Sequence/StatusNode",uid="{status_uid}",result="{result}"}} 1"#
+        )));
+    }
+
+    let histogram = format!(
+        r#"behaviortree_node_tick_duration_seconds_bucket{{{tree_label},path="main/Sequence",uid="{sequence_uid}",le="+Inf"}} 2"#
+    );
+    assert!(metrics.contains(&histogram));
+    assert!(metrics.contains(&format!(
+        r#"behaviortree_node_tick_duration_seconds_count{{{tree_label},path="main/Sequence",uid="{sequence_uid}"}} 2"#
+    )));
+
+    // Every sample has a value
+    for line in metrics.lines().filter(|l| !l.starts_with('#')) {
+        let value = line.rsplit(' ').next().unwrap();
+        assert!(value.parse::<f64>().is_ok(), "{line}");
+    }
+}
+
+#[test]
+fn prometheus_exporter_same_path() {
+    nodes::test_setup();
+
+    // Both StatusNodes have the path main/Sequence/StatusNode
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <StatusNode status="Failure" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    let exporter = PrometheusExporter::new();
+    tree.add_logger(exporter.tree_logger("main"));
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    let path = "main/Sequence/StatusNode".to_string();
+    let mut uids = Vec::new();
+    tree.visit(|node, _| {
+        if *node.path() == path {
+            uids.push(node.config().uid);
+        }
+    });
+    assert_eq!(uids.len(), 2);
+
+    // Each sibling has its own series
+    let metrics = exporter.render();
+    for (uid, result) in uids.iter().zip(["success", "failure"]) {
+        assert!(metrics.contains(&format!(
+            r#"behaviortree_node_results_total{{tree="main",path="{path}",uid="{uid}",result="{result}"}} 1"#
+        )));
+    }
+}
+
+#[cfg(feature = "prometheus-server")]
+#[test]
+fn prometheus_server() {
+    use std::io::{Read, Write};
+
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("status", NodeStatus::Success);
+
+    let mut tree = metrics_tree(&mut factory, &blackboard);
+
+    let exporter = PrometheusExporter::new();
+    tree.add_logger(exporter.tree_logger("main"));
+    tree.tick_while_running().unwrap();
+
+    let server = exporter.serve("127.0.0.1:0").unwrap();
+
+    let mut stream = std::net::TcpStream::connect(server.local_addr()).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with(&exporter.render()));
+
+    // The server stops listening when dropped
+    let addr = server.local_addr();
+    drop(server);
+    assert!(std::net::TcpStream::connect(addr).is_err());
+}
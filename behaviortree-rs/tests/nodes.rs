@@ -1,7 +1,15 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
 use behaviortree_rs::{
     basic_types::{BTToString, NodeStatus, PortsList},
-    macros::{define_ports, input_port},
-    nodes::{AsyncHalt, AsyncStatefulActionNode, AsyncTick, NodePorts, NodeResult},
+    macros::{define_ports, input_port, output_port, register_action_node},
+    nodes::{
+        action::SyncStatefulActionNode, AsyncHalt, AsyncStatefulActionNode, AsyncTick, NodePorts,
+        NodeResult,
+    },
+    tree::Factory,
 };
 use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
@@ -14,11 +22,23 @@ pub fn test_setup() {
         .try_init();
 }
 
+/// A `Factory` with the action nodes of this module registered.
+pub fn test_factory() -> Factory {
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    register_action_node!(factory, "SlowNode", SlowNode);
+    register_action_node!(factory, "CountNode", CountNode);
+    register_action_node!(factory, "WaitNode", WaitNode);
+
+    factory
+}
+
 #[bt_node(SyncActionNode)]
 pub struct StatusNode {}
 
 impl AsyncTick for StatusNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let status: NodeStatus = self.config.get_input("status").await?;
 
@@ -44,7 +64,7 @@ pub struct SuccessThenFailure {
 }
 
 impl AsyncTick for SuccessThenFailure {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let max_iters: usize = self.config.get_input("iters").await?;
 
@@ -72,7 +92,7 @@ impl AsyncHalt for SuccessThenFailure {}
 pub struct EchoNode {}
 
 impl AsyncTick for EchoNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let msg: String = self.config.get_input("msg").await?;
 
@@ -107,7 +127,7 @@ impl NodePorts for RunForNode {
 }
 
 impl AsyncStatefulActionNode for RunForNode {
-    fn on_start(&mut self) -> BoxFuture<NodeResult> {
+    fn on_start(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             info!("on_start()");
 
@@ -115,7 +135,7 @@ impl AsyncStatefulActionNode for RunForNode {
         })
     }
 
-    fn on_running(&mut self) -> BoxFuture<NodeResult> {
+    fn on_running(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move {
             let limit: usize = self.config.get_input("iters").await?;
 
@@ -138,9 +158,79 @@ pub struct DataNode {
 impl NodePorts for DataNode {}
 
 impl AsyncTick for DataNode {
-    fn tick(&mut self) -> BoxFuture<NodeResult> {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
         Box::pin(async move { Ok(NodeStatus::Success) })
     }
 }
 
 impl AsyncHalt for DataNode {}
+
+/// Takes 30ms to succeed.
+#[bt_node(SyncActionNode)]
+pub struct SlowNode {}
+
+impl NodePorts for SlowNode {}
+
+impl AsyncTick for SlowNode {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl AsyncHalt for SlowNode {}
+
+/// Counts its ticks into the `count` port, using the sync methods of `NodeConfig`.
+#[bt_node(StatefulActionNode, Sync)]
+pub struct CountNode {
+    #[bt(default)]
+    counter: usize,
+}
+
+impl NodePorts for CountNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("iters"), output_port!("count"))
+    }
+}
+
+impl SyncStatefulActionNode for CountNode {
+    fn on_start(&mut self) -> NodeResult {
+        self.counter = 0;
+
+        Ok(NodeStatus::Running)
+    }
+
+    fn on_running(&mut self) -> NodeResult {
+        let limit: usize = self.config.get_input_sync("iters")?;
+
+        self.counter += 1;
+        self.config.set_output_sync("count", self.counter)?;
+
+        if self.counter < limit {
+            Ok(NodeStatus::Running)
+        } else {
+            Ok(NodeStatus::Success)
+        }
+    }
+}
+
+/// Waits for a timer before succeeding.
+#[bt_node(SyncActionNode)]
+pub struct WaitNode {}
+
+impl NodePorts for WaitNode {}
+
+impl AsyncTick for WaitNode {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            futures_timer::Delay::new(Duration::from_millis(1)).await;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl AsyncHalt for WaitNode {}
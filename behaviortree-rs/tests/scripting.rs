@@ -0,0 +1,174 @@
+use behaviortree_rs::{
+    blackboard::Blackboard,
+    scripting::{Script, ScriptError, ScriptValue},
+};
+
+fn execute(script: &str, blackboard: &mut Blackboard) -> Result<ScriptValue, ScriptError> {
+    Script::parse(script)?.execute_sync(blackboard)
+}
+
+#[test]
+fn expressions() {
+    let mut blackboard = Blackboard::create();
+
+    let cases = [
+        ("42", ScriptValue::Integer(42)),
+        ("0x1F", ScriptValue::Integer(31)),
+        ("2.5", ScriptValue::Real(2.5)),
+        ("'hello'", ScriptValue::String(String::from("hello"))),
+        ("\"hello\"", ScriptValue::String(String::from("hello"))),
+        ("!true", ScriptValue::Bool(false)),
+        ("1 < 2 && 2.5 >= 2", ScriptValue::Bool(true)),
+        ("false || 1 == 1.0", ScriptValue::Bool(true)),
+        ("true || false && false", ScriptValue::Bool(true)),
+        ("!(1 != 1)", ScriptValue::Bool(true)),
+        ("'abc' < 'abd'", ScriptValue::Bool(true)),
+        ("'3' == 3", ScriptValue::Bool(true)),
+        ("1e3", ScriptValue::Real(1000.0)),
+        ("2.5E-1", ScriptValue::Real(0.25)),
+        ("1 + 2 * 3", ScriptValue::Integer(7)),
+        ("(1 + 2) * 3", ScriptValue::Integer(9)),
+        ("10 - 4 - 3", ScriptValue::Integer(3)),
+        ("-2 * -3", ScriptValue::Integer(6)),
+        ("6 / 3", ScriptValue::Integer(2)),
+        ("7 / 2", ScriptValue::Real(3.5)),
+        ("1.5 + 1", ScriptValue::Real(2.5)),
+        ("'4' * 2", ScriptValue::Integer(8)),
+        (
+            "9223372036854775807 + 1",
+            ScriptValue::Real(9223372036854775808.0),
+        ),
+        ("1 + 2 > 2 && !(2 * 2 == 5)", ScriptValue::Bool(true)),
+        (
+            "'robot_' .. 1 + 1",
+            ScriptValue::String(String::from("robot_2")),
+        ),
+        ("1..2", ScriptValue::String(String::from("12"))),
+    ];
+
+    for (script, expected) in cases {
+        assert_eq!(execute(script, &mut blackboard), Ok(expected), "{script}");
+    }
+}
+
+#[test]
+fn blackboard_entries() {
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("small", 5u8);
+    blackboard.set_sync("name", String::from("robot"));
+
+    assert_eq!(
+        execute("value := 3; flag := value > 2; flag", &mut blackboard),
+        Ok(ScriptValue::Bool(true))
+    );
+    assert_eq!(blackboard.get_exact_sync::<i64>("value"), Some(3));
+    assert_eq!(blackboard.get_exact_sync::<bool>("flag"), Some(true));
+
+    // Assignments keep the type of the existing entry
+    execute("small = 7; value = 4.0", &mut blackboard).unwrap();
+    assert_eq!(blackboard.get_exact_sync::<u8>("small"), Some(7));
+    assert_eq!(blackboard.get_exact_sync::<i64>("value"), Some(4));
+
+    // Compound assignments update existing entries
+    execute(
+        "value += 2; value *= 3; value -= 1; small /= 7",
+        &mut blackboard,
+    )
+    .unwrap();
+    assert_eq!(blackboard.get_exact_sync::<i64>("value"), Some(17));
+    assert_eq!(blackboard.get_exact_sync::<u8>("small"), Some(1));
+
+    // Numbers stored as strings, e.g. from XML ports
+    blackboard.set_sync("text", String::from("4"));
+    assert_eq!(
+        execute("text * 2", &mut blackboard),
+        Ok(ScriptValue::Integer(8))
+    );
+    execute("text += 1", &mut blackboard).unwrap();
+    assert_eq!(
+        blackboard.get_exact_sync::<String>("text"),
+        Some(String::from("5"))
+    );
+
+    assert_eq!(
+        execute("name := name .. '_' .. small", &mut blackboard),
+        Ok(ScriptValue::String(String::from("robot_1")))
+    );
+    assert_eq!(
+        execute("name == 'robot_1'", &mut blackboard),
+        Ok(ScriptValue::Bool(true))
+    );
+
+    assert_eq!(
+        execute("small = 300", &mut blackboard),
+        Err(ScriptError::InvalidConversion(String::from("300"), "u8"))
+    );
+    assert_eq!(
+        execute("value = 1.5", &mut blackboard),
+        Err(ScriptError::InvalidConversion(
+            String::from("1.5"),
+            "integer"
+        ))
+    );
+    assert_eq!(
+        execute("missing = 1", &mut blackboard),
+        Err(ScriptError::AssignToUnknownEntry(String::from("missing")))
+    );
+    assert_eq!(
+        execute("missing > 1", &mut blackboard),
+        Err(ScriptError::UnknownEntry(String::from("missing")))
+    );
+    assert_eq!(
+        execute("name < 2", &mut blackboard),
+        Err(ScriptError::InvalidOperands("<", "string", "integer"))
+    );
+    assert_eq!(
+        execute("name + 2", &mut blackboard),
+        Err(ScriptError::InvalidOperands("+", "string", "integer"))
+    );
+    assert_eq!(
+        execute("-true", &mut blackboard),
+        Err(ScriptError::InvalidNegation("bool"))
+    );
+    assert_eq!(
+        execute("value / 0", &mut blackboard),
+        Err(ScriptError::DivisionByZero)
+    );
+    assert_eq!(
+        execute("missing += 1", &mut blackboard),
+        Err(ScriptError::UnknownEntry(String::from("missing")))
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(Script::parse(" ; ").unwrap_err(), ScriptError::Empty);
+    assert_eq!(
+        Script::parse("a := 'abc").unwrap_err(),
+        ScriptError::UnterminatedString(5)
+    );
+    assert_eq!(
+        Script::parse("a # b").unwrap_err(),
+        ScriptError::UnexpectedCharacter('#', 2)
+    );
+    assert_eq!(
+        Script::parse("1.2.3").unwrap_err(),
+        ScriptError::InvalidNumber(String::from("1.2.3"))
+    );
+    assert_eq!(
+        Script::parse("a b").unwrap_err(),
+        ScriptError::UnexpectedToken(String::from("identifier [b]"), 2, String::from("[;]"))
+    );
+    assert_eq!(
+        Script::parse("a += ").unwrap_err(),
+        ScriptError::UnexpectedEnd(String::from("a value"))
+    );
+    assert_eq!(
+        Script::parse("1 + * 2").unwrap_err(),
+        ScriptError::UnexpectedToken(String::from("[*]"), 4, String::from("a value"))
+    );
+    assert_eq!(
+        Script::parse("(1 < 2").unwrap_err(),
+        ScriptError::UnexpectedEnd(String::from("[)]"))
+    );
+}
@@ -0,0 +1,57 @@
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    macros::register_action_node,
+    nodes::{ActionNodePtr, ControlNodePtr, DecoratorNodePtr, TreeNodePtr},
+    tree::{AsyncTree, Factory, SyncTree, TreeHandle},
+};
+
+mod nodes;
+
+use nodes::RunForNode;
+
+fn assert_send<T: Send + 'static>() {}
+
+#[test]
+fn trees_are_send() {
+    assert_send::<AsyncTree>();
+    assert_send::<SyncTree>();
+    assert_send::<TreeHandle>();
+    assert_send::<TreeNodePtr>();
+    assert_send::<ControlNodePtr>();
+    assert_send::<DecoratorNodePtr>();
+    assert_send::<ActionNodePtr>();
+}
+
+#[tokio::test]
+async fn spawn_tree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Inverter>
+                    <SubTree ID="sub" />
+                </Inverter>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <RunForNode iters="2" status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &Blackboard::create())
+        .await
+        .unwrap();
+
+    let status = tokio::spawn(async move { tree.tick_while_running().await })
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), NodeStatus::Success);
+}
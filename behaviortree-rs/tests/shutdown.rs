@@ -0,0 +1,62 @@
+use behaviortree_rs::{basic_types::NodeStatus, blackboard::Blackboard};
+
+mod nodes;
+
+const XML: &str = r#"
+    <root>
+        <BehaviorTree ID="main">
+            <Inverter>
+                <RunForNode iters="2" _onHalted="halted := true" />
+            </Inverter>
+        </BehaviorTree>
+    </root>
+"#;
+
+#[test]
+fn drop_sync_tree_halts_running_nodes() {
+    nodes::test_setup();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(XML.to_string(), &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert!(tree.is_running());
+
+    drop(tree);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+}
+
+#[test]
+fn shutdown_sync_tree() {
+    nodes::test_setup();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(XML.to_string(), &blackboard)
+        .unwrap();
+
+    // Completed trees aren't halted
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+    assert!(!tree.is_running());
+
+    tree.shutdown();
+    assert_eq!(blackboard.get_sync::<bool>("halted"), None);
+}
+
+#[tokio::test]
+async fn shutdown_async_tree() {
+    nodes::test_setup();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(XML.to_string(), &blackboard)
+        .await
+        .unwrap();
+
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    tree.shutdown().await;
+    assert_eq!(blackboard.get::<bool>("halted").await, Some(true));
+}
@@ -0,0 +1,235 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    loggers::StatusChangeLogger,
+    nodes::{
+        action::{AsyncStatefulActionNode, SyncStatefulActionNode},
+        NodeConfig, NodeError, NodePorts, NodeResult, TreeNodeBase,
+    },
+    sync::BoxFuture,
+};
+use behaviortree_rs_derive::bt_node;
+
+mod nodes;
+
+/// Waits for a timer before running, then succeeds.
+#[bt_node(StatefulActionNode, Async)]
+struct AsyncWaitNode {}
+
+impl NodePorts for AsyncWaitNode {}
+
+impl AsyncStatefulActionNode for AsyncWaitNode {
+    fn on_start(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            futures_timer::Delay::new(Duration::from_millis(1)).await;
+
+            Ok(NodeStatus::Running)
+        })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Success) })
+    }
+}
+
+/// Records the start and end of every tick, with the name of the node and
+/// whether the tick returned an error.
+#[derive(Default)]
+struct TickLogger {
+    ticks: Vec<(&'static str, String, bool)>,
+}
+
+impl StatusChangeLogger for TickLogger {
+    fn on_status_change(
+        &mut self,
+        _timestamp: Duration,
+        _node: &dyn TreeNodeBase,
+        _prev_status: NodeStatus,
+        _status: NodeStatus,
+    ) {
+    }
+
+    fn on_tick_start(&mut self, _timestamp: Duration, node: &dyn TreeNodeBase) {
+        self.ticks.push(("start", node.name().clone(), false));
+    }
+
+    fn on_tick_end(&mut self, _timestamp: Duration, node: &dyn TreeNodeBase, result: &NodeResult) {
+        self.ticks
+            .push(("end", node.name().clone(), result.is_err()));
+    }
+}
+
+const COUNT_XML: &str = r#"
+    <root>
+        <BehaviorTree ID="main">
+            <CountNode iters="3" count="{count}" />
+        </BehaviorTree>
+    </root>
+"#;
+
+const WAIT_XML: &str = r#"
+    <root>
+        <BehaviorTree ID="main">
+            <WaitNode />
+        </BehaviorTree>
+    </root>
+"#;
+
+#[tokio::test]
+async fn sync_tree_inside_runtime() {
+    nodes::test_setup();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(COUNT_XML.to_string(), &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<usize>("count"), Some(3));
+
+    // Running nodes are halted without blocking too
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    tree.try_halt_tree().unwrap();
+    assert!(!tree.is_running());
+}
+
+#[tokio::test]
+async fn waiting_node_inside_runtime() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(WAIT_XML.to_string(), &Blackboard::create())
+        .unwrap();
+
+    assert!(matches!(tree.tick_once(), Err(NodeError::WouldBlock)));
+}
+
+#[tokio::test]
+async fn waiting_node_halts_tree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <CountNode iters="2" count="{count}" />
+                    <WaitNode />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#;
+
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(xml.to_string(), &Blackboard::create())
+        .unwrap();
+    let logger = Arc::new(Mutex::new(TickLogger::default()));
+    tree.add_logger(logger.clone());
+
+    assert!(matches!(tree.tick_once(), Err(NodeError::WouldBlock)));
+
+    // The tree is halted, so the next tick starts over
+    assert!(!tree.is_running());
+    tree.visit(|node, _| assert_eq!(node.status(), NodeStatus::Idle));
+
+    // Every tick started was ended, the interrupted ones with an error
+    let ticks = &logger.lock().unwrap().ticks;
+    let starts = ticks.iter().filter(|(event, ..)| *event == "start").count();
+    let ends = ticks.iter().filter(|(event, ..)| *event == "end").count();
+    assert_eq!(starts, ends);
+    assert_eq!(
+        ticks[ticks.len() - 3..],
+        [
+            ("end", "WaitNode".to_string(), true),
+            ("end", "Sequence".to_string(), true),
+            ("end", "main".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn async_stateful_node_from_sync_code() {
+    nodes::test_setup();
+
+    let mut node = AsyncWaitNode::new("wait", NodeConfig::new(Blackboard::create()));
+
+    assert_eq!(
+        SyncStatefulActionNode::on_start(&mut node).unwrap(),
+        NodeStatus::Running
+    );
+    assert_eq!(
+        SyncStatefulActionNode::on_running(&mut node).unwrap(),
+        NodeStatus::Success
+    );
+
+    // Inside a runtime, waiting would block
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        assert!(matches!(
+            SyncStatefulActionNode::on_start(&mut node),
+            Err(NodeError::WouldBlock)
+        ));
+    });
+}
+
+#[test]
+fn waiting_node_outside_runtime() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(WAIT_XML.to_string(), &Blackboard::create())
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn paused_sync_tree() {
+    nodes::test_setup();
+
+    let blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(COUNT_XML.to_string(), &blackboard)
+        .unwrap();
+    let handle = tree.handle();
+
+    handle.pause();
+    let resume = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        handle.resume();
+    });
+
+    // Blocks until the tree is resumed
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    resume.join().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_nodes_on_multi_thread_runtime() {
+    nodes::test_setup();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(COUNT_XML.to_string(), &blackboard)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+    assert_eq!(blackboard.get_sync::<usize>("count"), Some(3));
+
+    // Blocking in place still completes on the first poll
+    let mut tree = nodes::test_factory()
+        .create_sync_tree_from_text(COUNT_XML.to_string(), &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+}
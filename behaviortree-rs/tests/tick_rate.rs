@@ -0,0 +1,209 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    blackboard::Blackboard,
+    tick_rate::{CancelHandle, TickRate},
+};
+
+mod nodes;
+
+fn tree_xml(node: &str) -> String {
+    format!(
+        r#"
+        <root>
+            <BehaviorTree ID="main">
+                {node}
+            </BehaviorTree>
+        </root>
+    "#
+    )
+}
+
+#[tokio::test]
+async fn tick_while_running_with_period() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(
+            tree_xml(r#"<RunForNode iters="3" />"#),
+            &Blackboard::create(),
+        )
+        .await
+        .unwrap();
+
+    // Running for 4 ticks, then Success on the 5th
+    let start = Instant::now();
+    assert_eq!(
+        tree.tick_while_running_with_period(Duration::from_millis(10))
+            .await
+            .unwrap(),
+        NodeStatus::Success
+    );
+    assert!(start.elapsed() >= Duration::from_millis(40));
+}
+
+#[tokio::test]
+async fn tick_overruns() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(tree_xml("<SlowNode />"), &Blackboard::create())
+        .await
+        .unwrap();
+
+    let overruns = Arc::new(Mutex::new(Vec::new()));
+    let overruns_cb = overruns.clone();
+    let rate = TickRate::new(Duration::from_millis(10))
+        .on_overrun(move |overrun| overruns_cb.lock().unwrap().push(overrun));
+
+    assert_eq!(
+        tree.tick_while_running_at(rate).await.unwrap(),
+        NodeStatus::Success
+    );
+
+    let overruns = overruns.lock().unwrap().clone();
+    assert_eq!(overruns.len(), 1);
+    assert_eq!(overruns[0].tick, 0);
+    assert_eq!(overruns[0].period, Duration::from_millis(10));
+    assert!(overruns[0].duration >= Duration::from_millis(30));
+}
+
+#[tokio::test]
+async fn run_at_rate_until_cancelled() {
+    nodes::test_setup();
+
+    let mut factory = nodes::test_factory();
+
+    // Completes on every tick, and starts again
+    let mut tree = factory
+        .create_async_tree_from_text(
+            tree_xml(r#"<StatusNode status="Success" />"#),
+            &Blackboard::create(),
+        )
+        .await
+        .unwrap();
+
+    let cancel = CancelHandle::new();
+    let cancel_task = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel_task.cancel();
+    });
+
+    let rate = TickRate::new(Duration::from_millis(5)).cancel_on(cancel.clone());
+    tree.run_at_rate(rate).await.unwrap();
+    assert!(cancel.is_cancelled());
+
+    // The running tree is halted when cancelled
+    let mut tree = factory
+        .create_async_tree_from_text(
+            tree_xml(r#"<RunForNode iters="1000" />"#),
+            &Blackboard::create(),
+        )
+        .await
+        .unwrap();
+
+    let cancel = CancelHandle::new();
+    let rate = TickRate::new(Duration::from_millis(5)).cancel_on(cancel.clone());
+
+    let cancel_task = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel_task.cancel();
+    });
+
+    assert_eq!(
+        tree.tick_while_running_at(rate).await.unwrap(),
+        NodeStatus::Idle
+    );
+    assert_eq!(tree.root().status(), NodeStatus::Idle);
+}
+
+#[tokio::test]
+async fn cancel_during_tick() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(tree_xml("<SlowNode />"), &Blackboard::create())
+        .await
+        .unwrap();
+
+    // Cancelled before the 30ms tick of SlowNode completes
+    let cancel = CancelHandle::new();
+    let cancel_task = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cancel_task.cancel();
+    });
+
+    let rate = TickRate::new(Duration::from_secs(1)).cancel_on(cancel);
+    assert_eq!(
+        tree.tick_while_running_at(rate).await.unwrap(),
+        NodeStatus::Idle
+    );
+    assert_eq!(tree.root().status(), NodeStatus::Idle);
+}
+
+#[tokio::test]
+async fn paused_time_is_not_an_overrun() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(
+            tree_xml(r#"<RunForNode iters="2" />"#),
+            &Blackboard::create(),
+        )
+        .await
+        .unwrap();
+
+    let handle = tree.handle();
+    handle.pause();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.resume();
+    });
+
+    let overruns = Arc::new(Mutex::new(Vec::new()));
+    let overruns_cb = overruns.clone();
+    let rate = TickRate::new(Duration::from_millis(20))
+        .on_overrun(move |overrun| overruns_cb.lock().unwrap().push(overrun));
+
+    assert_eq!(
+        tree.tick_while_running_at(rate).await.unwrap(),
+        NodeStatus::Success
+    );
+    assert!(overruns.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn cancel_while_paused() {
+    nodes::test_setup();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(
+            tree_xml(r#"<RunForNode iters="2" />"#),
+            &Blackboard::create(),
+        )
+        .await
+        .unwrap();
+    tree.handle().pause();
+
+    let cancel = CancelHandle::new();
+    let cancel_task = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cancel_task.cancel();
+    });
+
+    // Returns without waiting for the tree to be resumed
+    let rate = TickRate::new(Duration::from_millis(5)).cancel_on(cancel);
+    assert_eq!(
+        tree.tick_while_running_at(rate).await.unwrap(),
+        NodeStatus::Idle
+    );
+    assert!(tree.handle().is_paused());
+}
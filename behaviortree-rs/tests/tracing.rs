@@ -0,0 +1,142 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use behaviortree_rs::{
+    basic_types::NodeStatus, blackboard::Blackboard, macros::register_action_node, tree::Factory,
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+mod nodes;
+
+use nodes::RunForNode;
+
+#[derive(Clone, Debug, Default)]
+struct RecordedSpan {
+    name: &'static str,
+    fields: HashMap<String, String>,
+    parent: Option<u64>,
+}
+
+impl Visit for RecordedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// Records every span, in order of creation.
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    stack: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let mut span = RecordedSpan {
+            name: attributes.metadata().name(),
+            parent: self.stack.lock().unwrap().last().copied(),
+            ..Default::default()
+        };
+        attributes.record(&mut span);
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut spans[span.into_u64() as usize - 1]);
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.stack.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.stack.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn node_spans() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Inverter>
+                    <RunForNode name="second" iters="10" />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let recorder = Recorder::default();
+    let spans = recorder.spans.clone();
+
+    tracing::subscriber::with_default(recorder, || {
+        futures::executor::block_on(async {
+            let mut factory = Factory::new();
+            register_action_node!(factory, "RunForNode", RunForNode);
+
+            let blackboard = Blackboard::create();
+            let mut tree = factory
+                .create_async_tree_from_text(xml, &blackboard)
+                .await
+                .unwrap();
+
+            assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+            tree.halt_tree().await;
+        })
+    });
+
+    let spans = spans.lock().unwrap().clone();
+    let find = |name: &str, node: &str| {
+        spans
+            .iter()
+            .position(|s| s.name == name && s.fields["node"] == node)
+            .unwrap_or_else(|| panic!("missing span {name} for {node}"))
+    };
+
+    let tree_tick = &spans[find("tick_tree", "main")];
+    assert_eq!(tree_tick.fields["method"], "tick_once");
+    assert_eq!(tree_tick.fields["status"], "RUNNING");
+
+    // Ticks are nested in the tick of their parent
+    let inverter = find("execute_tick", "Inverter");
+    let second = &spans[find("execute_tick", "second")];
+    assert_eq!(second.parent, Some(inverter as u64 + 1));
+    assert_eq!(second.fields["id"], "RunForNode");
+    assert_eq!(second.fields["status"], "RUNNING");
+    assert!(second.fields.contains_key("path"));
+
+    // Halting the tree halts every running node
+    let halt = &spans[find("halt", "second")];
+    assert_eq!(halt.fields["status"], "IDLE");
+    assert_eq!(halt.parent, Some(find("halt", "Inverter") as u64 + 1));
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use behaviortree_rs::{basic_types::NodeStatus, blackboard::Blackboard};
+
+mod nodes;
+
+#[tokio::test]
+async fn request_halt() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Inverter>
+                    <RunForNode iters="100000" _onHalted="halted := true" />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    let handle = tree.handle();
+    let supervisor = handle.clone();
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let status = supervisor.status();
+        supervisor.request_halt();
+        status
+    });
+
+    assert_eq!(tree.tick_while_running().await.unwrap(), NodeStatus::Idle);
+    assert_eq!(task.await.unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get::<bool>("halted").await, Some(true));
+    assert_eq!(tree.root().status(), NodeStatus::Idle);
+    assert_eq!(handle.status(), NodeStatus::Idle);
+    assert!(!handle.is_halt_requested());
+
+    // The tree starts again on the next tick
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+}
+
+#[tokio::test]
+async fn pause_and_resume() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <RunForNode iters="1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut tree = nodes::test_factory()
+        .create_async_tree_from_text(xml, &Blackboard::create())
+        .await
+        .unwrap();
+
+    let handle = tree.handle();
+    handle.pause();
+    assert!(handle.is_paused());
+
+    let supervisor = handle.clone();
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let status = supervisor.status();
+        supervisor.resume();
+        status
+    });
+
+    let start = Instant::now();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    // Not ticked while paused
+    assert_eq!(task.await.unwrap(), NodeStatus::Idle);
+    assert_eq!(handle.status(), NodeStatus::Running);
+
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+    assert_eq!(handle.status(), NodeStatus::Success);
+}
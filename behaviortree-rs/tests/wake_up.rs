@@ -0,0 +1,183 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use behaviortree_rs::{
+    basic_types::{NodeStatus, PortsList},
+    blackboard::Blackboard,
+    bt_node,
+    macros::{define_ports, input_port, register_action_node},
+    nodes::{AsyncStatefulActionNode, NodePorts, NodeResult},
+    tree::Factory,
+    wake_up::WakeUpSignal,
+};
+use futures::future::BoxFuture;
+
+mod nodes;
+
+/// Emits the wake-up signal on its first `wakes` ticks. Always returns
+/// `NodeStatus::Running` and counts its ticks in the blackboard.
+#[bt_node(StatefulActionNode)]
+struct WakeOnTickNode {}
+
+impl WakeOnTickNode {
+    async fn count_tick(&mut self) -> NodeResult {
+        let wakes: usize = self.config.get_input("wakes").await?;
+        let ticks = self
+            .config
+            .blackboard
+            .get::<usize>("ticks")
+            .await
+            .unwrap_or_default()
+            + 1;
+        self.config.blackboard.set("ticks", ticks).await;
+
+        if ticks <= wakes {
+            self.config.emit_wake_up_signal();
+        }
+
+        Ok(NodeStatus::Running)
+    }
+}
+
+impl NodePorts for WakeOnTickNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("wakes"))
+    }
+}
+
+impl AsyncStatefulActionNode for WakeOnTickNode {
+    fn on_start(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(self.count_tick())
+    }
+
+    fn on_running(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(self.count_tick())
+    }
+}
+
+/// Completes after some work done in a background thread, which emits the
+/// wake-up signal when it's done.
+#[bt_node(StatefulActionNode)]
+struct BackgroundNode {
+    #[bt(default)]
+    done: Arc<AtomicBool>,
+}
+
+impl NodePorts for BackgroundNode {}
+
+impl AsyncStatefulActionNode for BackgroundNode {
+    fn on_start(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let done = self.done.clone();
+            let signal: WakeUpSignal = self.config.wake_up_signal();
+
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                done.store(true, Ordering::SeqCst);
+                signal.emit();
+            });
+
+            Ok(NodeStatus::Running)
+        })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            if self.done.load(Ordering::SeqCst) {
+                Ok(NodeStatus::Success)
+            } else {
+                Ok(NodeStatus::Running)
+            }
+        })
+    }
+}
+
+#[test]
+fn tick_once_until_not_woken_up() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <WakeOnTickNode wakes="2" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "WakeOnTickNode", WakeOnTickNode);
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    // Not ticked again, even though the signal was emitted
+    assert_eq!(tree.tick_exactly_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<usize>("ticks"), Some(1));
+
+    // Ticked again after the second tick emitted the signal, but not after
+    // the third one
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<usize>("ticks"), Some(3));
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<usize>("ticks"), Some(4));
+}
+
+#[test]
+fn tick_while_running_sleeps_until_woken_up() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <BackgroundNode />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "BackgroundNode", BackgroundNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    let start = Instant::now();
+    assert_eq!(
+        tree.tick_while_running_with_sleep(Duration::from_secs(30))
+            .unwrap(),
+        NodeStatus::Success
+    );
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn wake_up_signal() {
+    let signal = WakeUpSignal::new();
+    assert!(!signal.take());
+
+    // Emitting the signal several times wakes up once
+    let clone = signal.clone();
+    clone.emit();
+    clone.emit();
+    assert!(signal.take());
+    assert!(!signal.take());
+
+    assert!(!signal.wait_for(Duration::from_millis(1)).await);
+
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        clone.emit();
+    });
+
+    assert!(signal.wait_for(Duration::from_secs(30)).await);
+}
@@ -0,0 +1,281 @@
+use behaviortree_rs::{
+    basic_types::{NodeStatus, NodeType, PortDirection},
+    blackboard::Blackboard,
+    macros::register_action_node,
+    tree::{Factory, ParseError},
+};
+
+mod nodes;
+
+use nodes::{RunForNode, StatusNode};
+
+#[test]
+fn tree_to_xml_round_trip() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence name="main_sequence">
+                    <StatusNode status="Success" _onSuccess="done := 1 &lt; 2" />
+                    <SubTree ID="sub" name="first" status="{outer}" />
+                    <SubTree ID="sub" _autoremap="true" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Retry num_attempts="2">
+                    <StatusNode name="inner" status="{status}" />
+                </Retry>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("outer", NodeStatus::Success);
+    blackboard.set_sync("status", NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let generated = tree.to_xml().unwrap();
+
+    assert!(generated.contains(r#"<root BTCPP_format="4" main_tree_to_execute="main">"#));
+    assert!(generated.contains(r#"<Sequence name="main_sequence">"#));
+    assert!(generated.contains(r#"<SubTree name="first" ID="sub" status="{outer}"/>"#));
+    assert!(generated.contains(r#"<SubTree ID="sub" _autoremap="true"/>"#));
+    assert!(generated.contains(r#"<StatusNode status="Success" _onSuccess="done := 1 &lt; 2"/>"#));
+    assert!(generated.contains(r#"<StatusNode name="inner" status="{status}"/>"#));
+    // The subtree is only defined once, even though it's referenced twice
+    assert_eq!(generated.matches(r#"<BehaviorTree ID="sub">"#).count(), 1);
+
+    let expected = tree.tick_while_running().unwrap();
+
+    // Load the generated XML and make sure it behaves the same
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(generated.clone(), &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), expected);
+    assert_eq!(tree.to_xml().unwrap(), generated);
+}
+
+#[test]
+fn factory_write_tree_to_xml() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <SubTree ID="one" />
+                    <StatusNode status="Success" />
+                </Fallback>
+            </BehaviorTree>
+
+            <BehaviorTree ID="one">
+                <SubTree ID="two" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="two">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="unused">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    factory.register_bt_from_text(xml).unwrap();
+
+    // Nodes don't need to be registered to write the XML
+    let generated = factory.write_tree_to_xml("one").unwrap();
+
+    assert!(generated.contains(r#"main_tree_to_execute="one""#));
+    assert!(generated.contains(r#"<BehaviorTree ID="one">"#));
+    assert!(generated.contains(r#"<BehaviorTree ID="two">"#));
+    assert!(!generated.contains(r#"<BehaviorTree ID="main">"#));
+    assert!(!generated.contains(r#"<BehaviorTree ID="unused">"#));
+
+    let generated = factory.write_tree_to_xml("main").unwrap();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(generated, &Blackboard::create())
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    assert!(matches!(
+        Factory::new().write_tree_to_xml("missing"),
+        Err(ParseError::UnknownTree(_))
+    ));
+}
+
+#[test]
+fn parse_tree_nodes_model() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <TreeNodesModel>
+                <Action ID="StatusNode">
+                    <input_port name="status">Status to return</input_port>
+                </Action>
+                <Condition ID="Unregistered" description="Not registered yet">
+                    <input_port name="foo" default="1" />
+                    <output_port name="bar" />
+                </Condition>
+                <SubTree ID="sub">
+                    <input_port name="status" default="Success" />
+                </SubTree>
+            </TreeNodesModel>
+
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="{status}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    let model = factory.tree_nodes_model();
+    assert_eq!(model.len(), 3);
+
+    let status_node = &model["StatusNode"];
+    assert_eq!(status_node.node_type, NodeType::Action);
+    assert_eq!(
+        status_node.ports["status"].description(),
+        "Status to return"
+    );
+
+    let unregistered = &model["Unregistered"];
+    assert_eq!(unregistered.node_type, NodeType::Condition);
+    assert_eq!(unregistered.description, "Not registered yet");
+    assert_eq!(
+        unregistered.ports["foo"].default_value(),
+        Some(&String::from("1"))
+    );
+    assert_eq!(
+        unregistered.ports["bar"].direction(),
+        &PortDirection::Output
+    );
+
+    // The SubTree port default is used since the port isn't set in the XML
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn invalid_tree_nodes_model() {
+    nodes::test_setup();
+
+    let invalid_models = [
+        // Wrong node type
+        r#"<Decorator ID="StatusNode" />"#,
+        // Port not provided by node
+        r#"<Action ID="StatusNode"><input_port name="missing" /></Action>"#,
+        // Wrong port direction
+        r#"<Action ID="StatusNode"><output_port name="status" /></Action>"#,
+        // Unknown port tag
+        r#"<Action ID="StatusNode"><port name="status" /></Action>"#,
+        // Unknown node type
+        r#"<Leaf ID="Foo" />"#,
+    ];
+
+    for model in invalid_models {
+        let xml = format!(
+            r#"
+            <root>
+                <TreeNodesModel>
+                    {model}
+                </TreeNodesModel>
+            </root>
+            "#
+        );
+
+        let mut factory = Factory::new();
+        register_action_node!(factory, "StatusNode", StatusNode);
+
+        assert!(
+            matches!(
+                factory.register_bt_from_text(xml).unwrap_err().inner(),
+                ParseError::InvalidNodeModel(_, _)
+            ),
+            "{model}"
+        );
+    }
+
+    // Models without ID are skipped
+    let xml = r#"
+        <root>
+            <TreeNodesModel>
+                <Action />
+                <Action><input_port name="status" /></Action>
+                <Action ID="StatusNode" />
+            </TreeNodesModel>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    factory.register_bt_from_text(xml).unwrap();
+    assert_eq!(factory.tree_nodes_model().len(), 1);
+    assert!(factory.tree_nodes_model().contains_key("StatusNode"));
+}
+
+#[test]
+fn tree_nodes_model_xml() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let generated = factory.tree_nodes_model_xml().unwrap();
+
+    let expected = r#"<root BTCPP_format="4">
+    <TreeNodesModel>
+        <Action ID="RunForNode">
+            <input_port name="iters"/>
+            <input_port name="status" default="SUCCESS"/>
+        </Action>
+        <Action ID="StatusNode">
+            <input_port name="status"/>
+        </Action>
+    </TreeNodesModel>
+</root>"#;
+
+    assert_eq!(generated, expected);
+
+    // The generated model can be loaded back
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    factory.register_bt_from_text(generated).unwrap();
+
+    assert_eq!(factory.tree_nodes_model().len(), 2);
+}